@@ -0,0 +1,354 @@
+//! A command-line tool to build circuits from a [`CircuitDescription`], prove them from a witness
+//! file, verify the resulting proofs and inspect their size.
+//!
+//! Circuits are either read from a JSON description or taken from the built-in examples, and
+//! artifacts are written in the [`versioned`](plonky2::util::serialization::versioned) format:
+//!
+//! ```text
+//! plonky2-cli build --example factors --output circuit.bin
+//! plonky2-cli prove --example factors --witness witness.json --output proof.bin
+//! plonky2-cli verify --verifier-data circuit.bin --proof proof.bin
+//! plonky2-cli inspect --verifier-data circuit.bin --proof proof.bin
+//! ```
+//!
+//! A witness file is a JSON array holding the canonical `u64` values of the circuit inputs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
+use plonky2::field::types::{Field, Field64};
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierCircuitData};
+use plonky2::plonk::circuit_description::{CircuitDescription, CircuitOperation};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::util::serialization::{DefaultGateSerializer, IoResult, Write};
+use structopt::StructOpt;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// The names of the built-in example circuits.
+const EXAMPLES: [&str; 2] = ["factors", "hash-preimage"];
+
+#[derive(Clone, StructOpt, Debug)]
+#[structopt(name = "plonky2-cli")]
+enum Command {
+    /// Builds a circuit and writes its verifier data.
+    Build {
+        #[structopt(flatten)]
+        circuit: CircuitSource,
+        /// Where to write the verifier data.
+        #[structopt(long, short, parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Proves a circuit with the inputs of a witness file, and writes the proof.
+    Prove {
+        #[structopt(flatten)]
+        circuit: CircuitSource,
+        /// A JSON array of the canonical values of the circuit inputs.
+        #[structopt(long, short, parse(from_os_str))]
+        witness: PathBuf,
+        /// Where to write the proof.
+        #[structopt(long, short, parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Verifies a proof against verifier data.
+    Verify {
+        /// The verifier data, as written by `build`.
+        #[structopt(long, parse(from_os_str))]
+        verifier_data: PathBuf,
+        /// The proof, as written by `prove`.
+        #[structopt(long, short, parse(from_os_str))]
+        proof: PathBuf,
+    },
+    /// Prints the parameters of a circuit and, if a proof is given, the size of its components.
+    Inspect {
+        /// The verifier data, as written by `build`.
+        #[structopt(long, parse(from_os_str))]
+        verifier_data: PathBuf,
+        /// A proof of the circuit, as written by `prove`.
+        #[structopt(long, short, parse(from_os_str))]
+        proof: Option<PathBuf>,
+    },
+    /// Prints the JSON description of a built-in example circuit.
+    Example {
+        /// The name of the example.
+        name: String,
+    },
+}
+
+#[derive(Clone, StructOpt, Debug)]
+struct CircuitSource {
+    /// A JSON circuit description.
+    #[structopt(long, parse(from_os_str), required_unless = "example")]
+    circuit: Option<PathBuf>,
+    /// The name of a built-in example circuit, one of `factors` and `hash-preimage`.
+    #[structopt(long, conflicts_with = "circuit")]
+    example: Option<String>,
+}
+
+impl CircuitSource {
+    fn description(&self) -> Result<CircuitDescription> {
+        match (&self.circuit, &self.example) {
+            (Some(path), _) => Ok(serde_json::from_slice(&read(path)?)?),
+            (None, Some(name)) => example(name),
+            (None, None) => bail!("Either a circuit description or an example is required"),
+        }
+    }
+}
+
+/// Outputs the description of a built-in example circuit.
+fn example(name: &str) -> Result<CircuitDescription> {
+    let operations = match name {
+        // Proves knowledge of a factorization of a public value into two 32-bit factors.
+        "factors" => vec![
+            CircuitOperation::RangeCheck { value: 0, bits: 32 },
+            CircuitOperation::RangeCheck { value: 1, bits: 32 },
+            CircuitOperation::Mul { lhs: 0, rhs: 1 },
+            CircuitOperation::PublicInput { value: 2 },
+        ],
+        // Proves knowledge of a preimage of a public Poseidon hash.
+        "hash-preimage" => vec![
+            CircuitOperation::Hash { inputs: vec![0, 1] },
+            CircuitOperation::PublicInput { value: 2 },
+            CircuitOperation::PublicInput { value: 3 },
+            CircuitOperation::PublicInput { value: 4 },
+            CircuitOperation::PublicInput { value: 5 },
+        ],
+        _ => bail!(
+            "Unknown example {}, expected one of {}",
+            name,
+            EXAMPLES.join(", ")
+        ),
+    };
+    Ok(CircuitDescription {
+        num_inputs: 2,
+        operations,
+    })
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<()> {
+    fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn read_verifier_data(path: &Path) -> Result<VerifierCircuitData<F, C, D>> {
+    VerifierCircuitData::from_versioned_bytes(&read(path)?, &DefaultGateSerializer)
+}
+
+fn read_witness(path: &Path) -> Result<Vec<F>> {
+    let values: Vec<u64> = serde_json::from_slice(&read(path)?)?;
+    values
+        .into_iter()
+        .map(|v| {
+            ensure!(v < F::ORDER, "Witness value {} is not canonical", v);
+            Ok(F::from_canonical_u64(v))
+        })
+        .collect()
+}
+
+/// The serialized size, in bytes, of each component of a proof.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct ProofSizes {
+    public_inputs: usize,
+    wires_cap: usize,
+    plonk_zs_partial_products_cap: usize,
+    quotient_polys_cap: usize,
+    openings: usize,
+    fri_commit_phase_merkle_caps: usize,
+    fri_query_round_proofs: usize,
+    fri_final_poly: usize,
+    fri_pow_witness: usize,
+}
+
+impl ProofSizes {
+    fn new(proof_with_pis: &ProofWithPublicInputs<F, C, D>) -> Self {
+        fn size(write: impl FnOnce(&mut Vec<u8>) -> IoResult<()>) -> usize {
+            let mut buffer = Vec::new();
+            write(&mut buffer).expect("Writing to a byte-vector cannot fail.");
+            buffer.len()
+        }
+
+        let proof = &proof_with_pis.proof;
+        let fri_proof = &proof.opening_proof;
+        Self {
+            public_inputs: size(|b| b.write_field_vec(&proof_with_pis.public_inputs)),
+            wires_cap: size(|b| b.write_merkle_cap(&proof.wires_cap)),
+            plonk_zs_partial_products_cap: size(|b| {
+                b.write_merkle_cap(&proof.plonk_zs_partial_products_cap)
+            }),
+            quotient_polys_cap: size(|b| b.write_merkle_cap(&proof.quotient_polys_cap)),
+            openings: size(|b| b.write_opening_set(&proof.openings)),
+            fri_commit_phase_merkle_caps: size(|b| {
+                fri_proof
+                    .commit_phase_merkle_caps
+                    .iter()
+                    .try_for_each(|cap| b.write_merkle_cap(cap))
+            }),
+            fri_query_round_proofs: size(|b| {
+                b.write_fri_query_rounds::<F, C, D>(&fri_proof.query_round_proofs)
+            }),
+            fri_final_poly: size(|b| b.write_field_ext_vec::<F, D>(&fri_proof.final_poly.coeffs)),
+            fri_pow_witness: size(|b| b.write_field(fri_proof.pow_witness)),
+        }
+    }
+
+    fn components(&self) -> [(&'static str, usize); 9] {
+        [
+            ("public inputs", self.public_inputs),
+            ("wires cap", self.wires_cap),
+            (
+                "plonk zs and partial products cap",
+                self.plonk_zs_partial_products_cap,
+            ),
+            ("quotient polys cap", self.quotient_polys_cap),
+            ("openings", self.openings),
+            (
+                "FRI commit phase merkle caps",
+                self.fri_commit_phase_merkle_caps,
+            ),
+            ("FRI query round proofs", self.fri_query_round_proofs),
+            ("FRI final poly", self.fri_final_poly),
+            ("FRI pow witness", self.fri_pow_witness),
+        ]
+    }
+
+    fn total(&self) -> usize {
+        self.components().iter().map(|&(_, size)| size).sum()
+    }
+}
+
+fn print_circuit(common: &CommonCircuitData<F, D>) {
+    let fri_params = &common.fri_params;
+    println!("Degree: 2^{}", common.degree_bits());
+    println!("Public inputs: {}", common.num_public_inputs);
+    println!("Constraint degree: {}", common.quotient_degree_factor + 1);
+    println!(
+        "Gates: {}",
+        common
+            .gates
+            .iter()
+            .map(|gate| gate.0.id())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    println!("FRI parameters:");
+    println!("  rate bits: {}", fri_params.config.rate_bits);
+    println!("  cap height: {}", fri_params.config.cap_height);
+    println!(
+        "  proof of work bits: {}",
+        fri_params.config.proof_of_work_bits
+    );
+    println!("  query rounds: {}", fri_params.config.num_query_rounds);
+    println!(
+        "  reduction arity bits: {:?}",
+        fri_params.reduction_arity_bits
+    );
+    println!("  hiding: {}", fri_params.hiding);
+    println!("  security bits: {}", common.config.security_bits);
+}
+
+fn print_proof_sizes(sizes: &ProofSizes) {
+    println!("Proof size: {} bytes", sizes.total());
+    for (name, size) in sizes.components() {
+        println!("  {}: {} bytes", name, size);
+    }
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Build { circuit, output } => {
+            let circuit = circuit
+                .description()?
+                .build::<F, C, D>(CircuitConfig::standard_recursion_config())?;
+            let verifier_data = circuit.data.verifier_data();
+            print_circuit(&verifier_data.common);
+            write(
+                &output,
+                &verifier_data.to_versioned_bytes(&DefaultGateSerializer)?,
+            )
+        }
+        Command::Prove {
+            circuit,
+            witness,
+            output,
+        } => {
+            let circuit = circuit
+                .description()?
+                .build::<F, C, D>(CircuitConfig::standard_recursion_config())?;
+            let proof = circuit.prove(&read_witness(&witness)?)?;
+            print_proof_sizes(&ProofSizes::new(&proof));
+            write(&output, &proof.to_versioned_bytes(&circuit.data.common))
+        }
+        Command::Verify {
+            verifier_data,
+            proof,
+        } => {
+            let verifier_data = read_verifier_data(&verifier_data)?;
+            let proof =
+                ProofWithPublicInputs::from_versioned_bytes(&read(&proof)?, &verifier_data.common)?;
+            verifier_data.verify(proof)?;
+            println!("Proof verified");
+            Ok(())
+        }
+        Command::Inspect {
+            verifier_data,
+            proof,
+        } => {
+            let verifier_data = read_verifier_data(&verifier_data)?;
+            print_circuit(&verifier_data.common);
+            if let Some(proof) = proof {
+                let proof = ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(
+                    &read(&proof)?,
+                    &verifier_data.common,
+                )?;
+                print_proof_sizes(&ProofSizes::new(&proof));
+            }
+            Ok(())
+        }
+        Command::Example { name } => {
+            println!("{}", serde_json::to_string_pretty(&example(&name)?)?);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    run(Command::from_args())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proof_sizes() -> Result<()> {
+        let circuit =
+            example("factors")?.build::<F, C, D>(CircuitConfig::standard_recursion_config())?;
+        let proof = circuit.prove(&[F::from_canonical_u32(3), F::from_canonical_u32(7)])?;
+        assert_eq!(proof.public_inputs, vec![F::from_canonical_u32(21)]);
+
+        let mut bytes = Vec::new();
+        bytes
+            .write_field_vec(&proof.public_inputs)
+            .and_then(|()| bytes.write_proof(&proof.proof))
+            .expect("Writing to a byte-vector cannot fail.");
+        assert_eq!(ProofSizes::new(&proof).total(), bytes.len());
+
+        circuit.data.verify(proof)
+    }
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        for name in EXAMPLES {
+            example(name)?.build::<F, C, D>(CircuitConfig::standard_recursion_config())?;
+        }
+        assert!(example("unknown").is_err());
+        Ok(())
+    }
+}
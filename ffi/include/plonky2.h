@@ -0,0 +1,61 @@
+/*
+ * C interface to the Plonky2 prover and verifier.
+ *
+ * See the documentation of the `plonky2_ffi` crate for the ownership rules of handles and
+ * buffers. All functions returning a `Plonky2Status` report failures through
+ * `plonky2_last_error_message`.
+ */
+
+#ifndef PLONKY2_H
+#define PLONKY2_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum Plonky2Status {
+    PLONKY2_OK = 0,
+    PLONKY2_NULL_POINTER = 1,
+    PLONKY2_INVALID_INPUT = 2,
+    PLONKY2_PROVING_FAILED = 3,
+    PLONKY2_VERIFICATION_FAILED = 4,
+    PLONKY2_PANIC = 5,
+} Plonky2Status;
+
+typedef struct Plonky2Buffer {
+    uint8_t *data;
+    size_t len;
+} Plonky2Buffer;
+
+typedef struct Plonky2Circuit Plonky2Circuit;
+typedef struct Plonky2Verifier Plonky2Verifier;
+typedef struct Plonky2Proof Plonky2Proof;
+
+const char *plonky2_last_error_message(void);
+
+void plonky2_buffer_free(Plonky2Buffer buffer);
+
+Plonky2Status plonky2_circuit_from_json(const uint8_t *json, size_t len, Plonky2Circuit **out);
+void plonky2_circuit_free(Plonky2Circuit *circuit);
+Plonky2Status plonky2_circuit_verifier_data(const Plonky2Circuit *circuit, Plonky2Buffer *out);
+Plonky2Status plonky2_circuit_prove(const Plonky2Circuit *circuit, const uint64_t *inputs,
+                                    size_t num_inputs, Plonky2Proof **out);
+
+Plonky2Status plonky2_verifier_from_bytes(const uint8_t *data, size_t len, Plonky2Verifier **out);
+void plonky2_verifier_free(Plonky2Verifier *verifier);
+Plonky2Status plonky2_verifier_verify(const Plonky2Verifier *verifier, const Plonky2Proof *proof);
+
+Plonky2Status plonky2_proof_from_bytes(const Plonky2Verifier *verifier, const uint8_t *data,
+                                       size_t len, Plonky2Proof **out);
+Plonky2Status plonky2_proof_to_bytes(const Plonky2Proof *proof, Plonky2Buffer *out);
+const uint64_t *plonky2_proof_public_inputs(const Plonky2Proof *proof, size_t *len);
+void plonky2_proof_free(Plonky2Proof *proof);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PLONKY2_H */
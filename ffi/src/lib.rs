@@ -0,0 +1,526 @@
+//! A C interface to the Plonky2 prover and verifier, built as a `cdylib`.
+//!
+//! Circuits are defined by a JSON [`CircuitDescription`] and use the
+//! [`PoseidonGoldilocksConfig`] with the standard recursion configuration. The interface
+//! exposes three opaque handles:
+//!
+//! - [`Plonky2Circuit`], a compiled circuit, which can produce proofs and verifier data;
+//! - [`Plonky2Verifier`], the verifier data of a circuit;
+//! - [`Plonky2Proof`], a proof along with its public inputs.
+//!
+//! Verifier data and proofs are exchanged as byte buffers in the
+//! [`versioned`](plonky2::util::serialization::versioned) format. Buffers returned by this
+//! library are [`Plonky2Buffer`]s, which must be released with [`plonky2_buffer_free`]. Handles
+//! must be released with their respective `_free` function.
+//!
+//! Every fallible function returns a [`Plonky2Status`]. On failure, a description of the error
+//! can be retrieved with [`plonky2_last_error_message`]. Panics are caught and reported as
+//! [`Plonky2Status::Panic`], so that they never unwind into the host.
+//!
+//! The C declarations of this interface are in `include/plonky2.h`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::{ptr, slice};
+
+use anyhow::{anyhow, Error};
+use plonky2::field::types::{Field, Field64, PrimeField64};
+use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierCircuitData};
+use plonky2::plonk::circuit_description::{CircuitDescription, DescribedCircuit};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::util::serialization::DefaultGateSerializer;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// The status returned by the functions of this interface.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Plonky2Status {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// An input (circuit description, witness, serialized artifact...) was invalid.
+    InvalidInput = 2,
+    /// Proof generation failed.
+    ProvingFailed = 3,
+    /// The proof did not verify.
+    VerificationFailed = 4,
+    /// The call panicked.
+    Panic = 5,
+}
+
+/// A byte buffer allocated by this library, to be released with [`plonky2_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct Plonky2Buffer {
+    /// The bytes of the buffer.
+    pub data: *mut u8,
+    /// The number of bytes of the buffer.
+    pub len: usize,
+}
+
+/// A compiled circuit.
+#[derive(Debug)]
+pub struct Plonky2Circuit(DescribedCircuit<F, C, D>);
+
+/// The verifier data of a circuit.
+#[derive(Debug)]
+pub struct Plonky2Verifier(VerifierCircuitData<F, C, D>);
+
+/// A proof along with its public inputs.
+#[derive(Debug)]
+pub struct Plonky2Proof {
+    proof: ProofWithPublicInputs<F, C, D>,
+    public_inputs: Vec<u64>,
+    common: CommonCircuitData<F, D>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Interior null bytes would truncate the message, so we replace them.
+    let message = CString::new(message.replace('\0', " ")).expect("No null bytes left.");
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Runs `f`, translating its errors and panics into a [`Plonky2Status`].
+fn run(f: impl FnOnce() -> Result<(), (Plonky2Status, Error)>) -> Plonky2Status {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => Plonky2Status::Ok,
+        Ok(Err((status, error))) => {
+            set_last_error(format!("{:#}", error));
+            status
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic".to_string());
+            set_last_error(format!("Panicked: {}", message));
+            Plonky2Status::Panic
+        }
+    }
+}
+
+trait WithStatus<T> {
+    fn status(self, status: Plonky2Status) -> Result<T, (Plonky2Status, Error)>;
+}
+
+impl<T, E: Into<Error>> WithStatus<T> for Result<T, E> {
+    fn status(self, status: Plonky2Status) -> Result<T, (Plonky2Status, Error)> {
+        self.map_err(|e| (status, e.into()))
+    }
+}
+
+fn non_null<T>(ptr: *const T, name: &str) -> Result<(), (Plonky2Status, Error)> {
+    if ptr.is_null() {
+        Err((Plonky2Status::NullPointer, anyhow!("{} is null", name)))
+    } else {
+        Ok(())
+    }
+}
+
+/// # Safety
+/// `data` must be valid for reads of `len` bytes, or null if `len` is zero.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], (Plonky2Status, Error)> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    non_null(data, "data")?;
+    Ok(slice::from_raw_parts(data, len))
+}
+
+fn into_buffer(bytes: Vec<u8>) -> Plonky2Buffer {
+    let bytes = Box::leak(bytes.into_boxed_slice());
+    Plonky2Buffer {
+        data: bytes.as_mut_ptr(),
+        len: bytes.len(),
+    }
+}
+
+/// Returns the message of the last error which occurred on the calling thread, or null if no
+/// error occurred. The message remains valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn plonky2_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Releases a buffer returned by this library.
+///
+/// # Safety
+/// `buffer` must have been returned by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_buffer_free(buffer: Plonky2Buffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+/// Compiles the circuit described by the JSON [`CircuitDescription`] in `json`.
+///
+/// # Safety
+/// `json` must be valid for reads of `len` bytes, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_circuit_from_json(
+    json: *const u8,
+    len: usize,
+    out: *mut *mut Plonky2Circuit,
+) -> Plonky2Status {
+    run(|| {
+        non_null(out, "out")?;
+        let description: CircuitDescription =
+            serde_json::from_slice(bytes(json, len)?).status(Plonky2Status::InvalidInput)?;
+        let circuit = description
+            .build(CircuitConfig::standard_recursion_config())
+            .status(Plonky2Status::InvalidInput)?;
+        *out = Box::into_raw(Box::new(Plonky2Circuit(circuit)));
+        Ok(())
+    })
+}
+
+/// Releases a circuit.
+///
+/// # Safety
+/// `circuit` must be null, or have been returned by [`plonky2_circuit_from_json`] and not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_circuit_free(circuit: *mut Plonky2Circuit) {
+    if !circuit.is_null() {
+        drop(Box::from_raw(circuit));
+    }
+}
+
+/// Serializes the verifier data of a circuit.
+///
+/// # Safety
+/// `circuit` must be a valid circuit handle, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_circuit_verifier_data(
+    circuit: *const Plonky2Circuit,
+    out: *mut Plonky2Buffer,
+) -> Plonky2Status {
+    run(|| {
+        non_null(circuit, "circuit")?;
+        non_null(out, "out")?;
+        let bytes = (*circuit)
+            .0
+            .data
+            .verifier_data()
+            .to_versioned_bytes(&DefaultGateSerializer)
+            .status(Plonky2Status::InvalidInput)?;
+        *out = into_buffer(bytes);
+        Ok(())
+    })
+}
+
+/// Proves a circuit with the given inputs, given by their canonical representations.
+///
+/// # Safety
+/// `circuit` must be a valid circuit handle, `inputs` must be valid for reads of `num_inputs`
+/// values, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_circuit_prove(
+    circuit: *const Plonky2Circuit,
+    inputs: *const u64,
+    num_inputs: usize,
+    out: *mut *mut Plonky2Proof,
+) -> Plonky2Status {
+    run(|| {
+        non_null(circuit, "circuit")?;
+        non_null(out, "out")?;
+        let inputs = if num_inputs == 0 {
+            &[]
+        } else {
+            non_null(inputs, "inputs")?;
+            slice::from_raw_parts(inputs, num_inputs)
+        };
+        let inputs = inputs
+            .iter()
+            .map(|&x| {
+                if x < F::ORDER {
+                    Ok(F::from_canonical_u64(x))
+                } else {
+                    Err((
+                        Plonky2Status::InvalidInput,
+                        anyhow!("Input {} is not canonical", x),
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit = &(*circuit).0;
+        let proof = circuit
+            .prove(&inputs)
+            .status(Plonky2Status::ProvingFailed)?;
+        *out = Box::into_raw(Box::new(Plonky2Proof::new(
+            proof,
+            circuit.data.common.clone(),
+        )));
+        Ok(())
+    })
+}
+
+impl Plonky2Proof {
+    fn new(proof: ProofWithPublicInputs<F, C, D>, common: CommonCircuitData<F, D>) -> Self {
+        Self {
+            public_inputs: proof
+                .public_inputs
+                .iter()
+                .map(|x| x.to_canonical_u64())
+                .collect(),
+            proof,
+            common,
+        }
+    }
+}
+
+/// Deserializes verifier data, as output by [`plonky2_circuit_verifier_data`].
+///
+/// # Safety
+/// `data` must be valid for reads of `len` bytes, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_verifier_from_bytes(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut Plonky2Verifier,
+) -> Plonky2Status {
+    run(|| {
+        non_null(out, "out")?;
+        let verifier =
+            VerifierCircuitData::from_versioned_bytes(bytes(data, len)?, &DefaultGateSerializer)
+                .status(Plonky2Status::InvalidInput)?;
+        *out = Box::into_raw(Box::new(Plonky2Verifier(verifier)));
+        Ok(())
+    })
+}
+
+/// Releases verifier data.
+///
+/// # Safety
+/// `verifier` must be null, or have been returned by [`plonky2_verifier_from_bytes`] and not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_verifier_free(verifier: *mut Plonky2Verifier) {
+    if !verifier.is_null() {
+        drop(Box::from_raw(verifier));
+    }
+}
+
+/// Verifies a proof, returning [`Plonky2Status::VerificationFailed`] if it is invalid.
+///
+/// # Safety
+/// `verifier` and `proof` must be valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_verifier_verify(
+    verifier: *const Plonky2Verifier,
+    proof: *const Plonky2Proof,
+) -> Plonky2Status {
+    run(|| {
+        non_null(verifier, "verifier")?;
+        non_null(proof, "proof")?;
+        (*verifier)
+            .0
+            .verify((*proof).proof.clone())
+            .status(Plonky2Status::VerificationFailed)
+    })
+}
+
+/// Deserializes a proof for the circuit of the given verifier data, as output by
+/// [`plonky2_proof_to_bytes`].
+///
+/// # Safety
+/// `verifier` must be a valid handle, `data` must be valid for reads of `len` bytes, and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_proof_from_bytes(
+    verifier: *const Plonky2Verifier,
+    data: *const u8,
+    len: usize,
+    out: *mut *mut Plonky2Proof,
+) -> Plonky2Status {
+    run(|| {
+        non_null(verifier, "verifier")?;
+        non_null(out, "out")?;
+        let common = &(*verifier).0.common;
+        let proof = ProofWithPublicInputs::from_versioned_bytes(bytes(data, len)?, common)
+            .status(Plonky2Status::InvalidInput)?;
+        *out = Box::into_raw(Box::new(Plonky2Proof::new(proof, common.clone())));
+        Ok(())
+    })
+}
+
+/// Serializes a proof.
+///
+/// # Safety
+/// `proof` must be a valid handle, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_proof_to_bytes(
+    proof: *const Plonky2Proof,
+    out: *mut Plonky2Buffer,
+) -> Plonky2Status {
+    run(|| {
+        non_null(proof, "proof")?;
+        non_null(out, "out")?;
+        let proof = &*proof;
+        *out = into_buffer(proof.proof.to_versioned_bytes(&proof.common));
+        Ok(())
+    })
+}
+
+/// Returns the public inputs of a proof, as canonical representations, and writes their number
+/// to `len`. The returned pointer is owned by the proof.
+///
+/// # Safety
+/// `proof` must be a valid handle, and `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_proof_public_inputs(
+    proof: *const Plonky2Proof,
+    len: *mut usize,
+) -> *const u64 {
+    if proof.is_null() || len.is_null() {
+        return ptr::null();
+    }
+    let public_inputs = &(*proof).public_inputs;
+    *len = public_inputs.len();
+    public_inputs.as_ptr()
+}
+
+/// Releases a proof.
+///
+/// # Safety
+/// `proof` must be null, or have been returned by this library and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn plonky2_proof_free(proof: *mut Plonky2Proof) {
+    if !proof.is_null() {
+        drop(Box::from_raw(proof));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    const FACTORS: &str = r#"{
+        "num_inputs": 2,
+        "operations": [
+            { "op": "mul", "lhs": 0, "rhs": 1 },
+            { "op": "public_input", "value": 2 }
+        ]
+    }"#;
+
+    fn last_error() -> String {
+        let message = plonky2_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_ffi_round_trip() {
+        unsafe {
+            let mut circuit = ptr::null_mut();
+            assert_eq!(
+                plonky2_circuit_from_json(FACTORS.as_ptr(), FACTORS.len(), &mut circuit),
+                Plonky2Status::Ok
+            );
+
+            let mut proof = ptr::null_mut();
+            let inputs = [3, 7];
+            assert_eq!(
+                plonky2_circuit_prove(circuit, inputs.as_ptr(), inputs.len(), &mut proof),
+                Plonky2Status::Ok
+            );
+            let mut len = 0;
+            let public_inputs = plonky2_proof_public_inputs(proof, &mut len);
+            assert_eq!(slice::from_raw_parts(public_inputs, len), [21]);
+
+            // Go through the serialized artifacts, as a host would.
+            let mut verifier_data = Plonky2Buffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(
+                plonky2_circuit_verifier_data(circuit, &mut verifier_data),
+                Plonky2Status::Ok
+            );
+            let mut verifier = ptr::null_mut();
+            assert_eq!(
+                plonky2_verifier_from_bytes(verifier_data.data, verifier_data.len, &mut verifier),
+                Plonky2Status::Ok
+            );
+            let mut proof_bytes = Plonky2Buffer {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            assert_eq!(
+                plonky2_proof_to_bytes(proof, &mut proof_bytes),
+                Plonky2Status::Ok
+            );
+            let mut decoded = ptr::null_mut();
+            assert_eq!(
+                plonky2_proof_from_bytes(verifier, proof_bytes.data, proof_bytes.len, &mut decoded),
+                Plonky2Status::Ok
+            );
+            assert_eq!(
+                plonky2_verifier_verify(verifier, decoded),
+                Plonky2Status::Ok
+            );
+
+            // A proof with different public inputs must not verify.
+            (&mut (*decoded).proof.public_inputs)[0] = F::from_canonical_u32(22);
+            assert_eq!(
+                plonky2_verifier_verify(verifier, decoded),
+                Plonky2Status::VerificationFailed
+            );
+
+            plonky2_proof_free(decoded);
+            plonky2_buffer_free(proof_bytes);
+            plonky2_verifier_free(verifier);
+            plonky2_buffer_free(verifier_data);
+            plonky2_proof_free(proof);
+            plonky2_circuit_free(circuit);
+        }
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        unsafe {
+            let mut circuit = ptr::null_mut();
+            let json = b"not json";
+            assert_eq!(
+                plonky2_circuit_from_json(json.as_ptr(), json.len(), &mut circuit),
+                Plonky2Status::InvalidInput
+            );
+            assert!(circuit.is_null());
+            assert!(!last_error().is_empty());
+
+            assert_eq!(
+                plonky2_circuit_from_json(FACTORS.as_ptr(), FACTORS.len(), ptr::null_mut()),
+                Plonky2Status::NullPointer
+            );
+            assert_eq!(last_error(), "out is null");
+
+            let mut verifier = ptr::null_mut();
+            let bytes = [0u8; 16];
+            assert_eq!(
+                plonky2_verifier_from_bytes(bytes.as_ptr(), bytes.len(), &mut verifier),
+                Plonky2Status::InvalidInput
+            );
+            assert!(verifier.is_null());
+        }
+    }
+}
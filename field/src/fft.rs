@@ -1,4 +1,5 @@
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cmp::{max, min};
 
 use plonky2_util::{log2_strict, reverse_index_bits_in_place};
@@ -38,8 +39,15 @@ fn fft_dispatch<F: Field>(
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
 ) {
+    #[cfg(any(test, feature = "std"))]
+    let computed_root_table = root_table
+        .is_none()
+        .then(|| crate::root_cache::cached_fft_root_table(log2_strict(input.len())));
+    #[cfg(not(any(test, feature = "std")))]
     let computed_root_table = root_table.is_none().then(|| fft_root_table(input.len()));
-    let used_root_table = root_table.or(computed_root_table.as_ref()).unwrap();
+    let used_root_table = root_table
+        .or(computed_root_table.as_ref().map(Borrow::borrow))
+        .unwrap();
 
     fft_classic(input, zero_factor.unwrap_or(0), used_root_table);
 }
@@ -56,10 +64,21 @@ pub fn fft_with_options<F: Field>(
     root_table: Option<&FftRootTable<F>>,
 ) -> PolynomialValues<F> {
     let PolynomialCoeffs { coeffs: mut buffer } = poly;
-    fft_dispatch(&mut buffer, zero_factor, root_table);
+    fft_in_place(&mut buffer, zero_factor, root_table);
     PolynomialValues::new(buffer)
 }
 
+/// Like [`fft_with_options`], but transforms the coefficients in `buffer` into values in place, so
+/// that they can live in any buffer, e.g. a memory-mapped file.
+#[inline]
+pub fn fft_in_place<F: Field>(
+    buffer: &mut [F],
+    zero_factor: Option<usize>,
+    root_table: Option<&FftRootTable<F>>,
+) {
+    fft_dispatch(buffer, zero_factor, root_table);
+}
+
 #[inline]
 pub fn ifft<F: Field>(poly: PolynomialValues<F>) -> PolynomialCoeffs<F> {
     ifft_with_options(poly, None, None)
@@ -70,12 +89,22 @@ pub fn ifft_with_options<F: Field>(
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
 ) -> PolynomialCoeffs<F> {
-    let n = poly.len();
+    let PolynomialValues { values: mut buffer } = poly;
+    ifft_in_place(&mut buffer, zero_factor, root_table);
+    PolynomialCoeffs { coeffs: buffer }
+}
+
+/// Like [`ifft_with_options`], but transforms the values in `buffer` into coefficients in place.
+pub fn ifft_in_place<F: Field>(
+    buffer: &mut [F],
+    zero_factor: Option<usize>,
+    root_table: Option<&FftRootTable<F>>,
+) {
+    let n = buffer.len();
     let lg_n = log2_strict(n);
     let n_inv = F::inverse_2exp(lg_n);
 
-    let PolynomialValues { values: mut buffer } = poly;
-    fft_dispatch(&mut buffer, zero_factor, root_table);
+    fft_dispatch(buffer, zero_factor, root_table);
 
     // We reverse all values except the first, and divide each by n.
     buffer[0] *= n_inv;
@@ -87,7 +116,6 @@ pub fn ifft_with_options<F: Field>(
         buffer[i] = coeffs_i;
         buffer[j] = coeffs_j;
     }
-    PolynomialCoeffs { coeffs: buffer }
 }
 
 /// Generic FFT implementation that works with both scalar and packed inputs.
@@ -1,3 +1,4 @@
+use core::cmp::Ordering;
 use core::fmt::{self, Debug, Display, Formatter};
 use core::hash::{Hash, Hasher};
 use core::iter::{Product, Sum};
@@ -38,6 +39,19 @@ impl PartialEq for GoldilocksField {
 
 impl Eq for GoldilocksField {}
 
+/// Orders elements by their canonical value in `[0, p)`.
+impl Ord for GoldilocksField {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_canonical_u64().cmp(&other.to_canonical_u64())
+    }
+}
+
+impl PartialOrd for GoldilocksField {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for GoldilocksField {
     fn hash<H: Hasher>(&self, state: &mut H) {
         state.write_u64(self.to_canonical_u64())
@@ -95,55 +109,11 @@ impl Field for GoldilocksField {
         Self::order()
     }
 
-    /// Returns the inverse of the field element, using Fermat's little theorem.
-    /// The inverse of `a` is computed as `a^(p-2)`, where `p` is the prime order of the field.
-    ///
-    /// Mathematically, this is equivalent to:
-    ///                $a^(p-1)     = 1 (mod p)$
-    ///                $a^(p-2) * a = 1 (mod p)$
-    /// Therefore      $a^(p-2)     = a^-1 (mod p)$
-    ///
-    /// The following code has been adapted from winterfell/math/src/field/f64/mod.rs
-    /// located at <https://github.com/facebook/winterfell>.
+    /// Returns the inverse of the field element, computed with
+    /// [`try_inverse_fermat`](GoldilocksField::try_inverse_fermat).
+    #[inline]
     fn try_inverse(&self) -> Option<Self> {
-        if self.is_zero() {
-            return None;
-        }
-
-        // compute base^(P - 2) using 72 multiplications
-        // The exponent P - 2 is represented in binary as:
-        // 0b1111111111111111111111111111111011111111111111111111111111111111
-
-        // compute base^11
-        let t2 = self.square() * *self;
-
-        // compute base^111
-        let t3 = t2.square() * *self;
-
-        // compute base^111111 (6 ones)
-        // repeatedly square t3 3 times and multiply by t3
-        let t6 = exp_acc::<3>(t3, t3);
-
-        // compute base^111111111111 (12 ones)
-        // repeatedly square t6 6 times and multiply by t6
-        let t12 = exp_acc::<6>(t6, t6);
-
-        // compute base^111111111111111111111111 (24 ones)
-        // repeatedly square t12 12 times and multiply by t12
-        let t24 = exp_acc::<12>(t12, t12);
-
-        // compute base^1111111111111111111111111111111 (31 ones)
-        // repeatedly square t24 6 times and multiply by t6 first. then square t30 and
-        // multiply by base
-        let t30 = exp_acc::<6>(t24, t6);
-        let t31 = t30.square() * *self;
-
-        // compute base^111111111111111111111111111111101111111111111111111111111111111
-        // repeatedly square t31 32 times and multiply by t31
-        let t63 = exp_acc::<32>(t31, t31);
-
-        // compute base^1111111111111111111111111111111011111111111111111111111111111111
-        Some(t63.square() * *self)
+        self.try_inverse_fermat()
     }
 
     fn from_noncanonical_biguint(n: BigUint) -> Self {
@@ -448,6 +418,166 @@ pub(crate) unsafe fn reduce160(x_lo: u128, x_hi: u32) -> GoldilocksField {
     GoldilocksField(t2)
 }
 
+impl GoldilocksField {
+    /// Returns the inverse of the field element, using Fermat's little theorem.
+    /// The inverse of `a` is computed as `a^(p-2)`, where `p` is the prime order of the field.
+    ///
+    /// Mathematically, this is equivalent to:
+    ///                $a^(p-1)     = 1 (mod p)$
+    ///                $a^(p-2) * a = 1 (mod p)$
+    /// Therefore      $a^(p-2)     = a^-1 (mod p)$
+    ///
+    /// The following code has been adapted from winterfell/math/src/field/f64/mod.rs
+    /// located at <https://github.com/facebook/winterfell>.
+    pub fn try_inverse_fermat(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // compute base^(P - 2) using 72 multiplications
+        // The exponent P - 2 is represented in binary as:
+        // 0b1111111111111111111111111111111011111111111111111111111111111111
+
+        // compute base^11
+        let t2 = self.square() * *self;
+
+        // compute base^111
+        let t3 = t2.square() * *self;
+
+        // compute base^111111 (6 ones)
+        // repeatedly square t3 3 times and multiply by t3
+        let t6 = exp_acc::<3>(t3, t3);
+
+        // compute base^111111111111 (12 ones)
+        // repeatedly square t6 6 times and multiply by t6
+        let t12 = exp_acc::<6>(t6, t6);
+
+        // compute base^111111111111111111111111 (24 ones)
+        // repeatedly square t12 12 times and multiply by t12
+        let t24 = exp_acc::<12>(t12, t12);
+
+        // compute base^1111111111111111111111111111111 (31 ones)
+        // repeatedly square t24 6 times and multiply by t6 first. then square t30 and
+        // multiply by base
+        let t30 = exp_acc::<6>(t24, t6);
+        let t31 = t30.square() * *self;
+
+        // compute base^111111111111111111111111111111101111111111111111111111111111111
+        // repeatedly square t31 32 times and multiply by t31
+        let t63 = exp_acc::<32>(t31, t31);
+
+        // compute base^1111111111111111111111111111111011111111111111111111111111111111
+        Some(t63.square() * *self)
+    }
+
+    /// Returns the inverse of the field element, using the safegcd algorithm of Bernstein and
+    /// Yang (<https://eprint.iacr.org/2019/266>), in the variant used by libsecp256k1: divsteps
+    /// are performed in batches of 59, each batch computing a transition matrix from the low bits
+    /// of `f` and `g` only, which is then applied to the full values and to the Bézout
+    /// coefficients.
+    ///
+    /// Apart from the check for zero, this runs in constant time: the number of divsteps is fixed,
+    /// and neither the divsteps nor the updates of the Bézout coefficients, which are kept as
+    /// signed integers rather than field elements, branch on the input. It is however slower than
+    /// [`try_inverse_fermat`](GoldilocksField::try_inverse_fermat) on x86-64, see the
+    /// `field_arithmetic` benchmark of `plonky2`, so [`Field::try_inverse`] doesn't use it.
+    pub fn try_inverse_safegcd(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // Invariants: `f = d * self` and `g = e * self` modulo the order, with `d` and `e` in
+        // `(-ORDER, ORDER)`. `f` stays odd.
+        let mut f = Self::ORDER as i128;
+        let mut g = self.to_canonical_u64() as i128;
+        let mut d = 0;
+        let mut e = 1;
+        let mut zeta = -1;
+        for _ in 0..SAFEGCD_BATCHES {
+            let (next_zeta, [u, v, q, r]) = divsteps_59(zeta, f as u64, g as u64);
+            zeta = next_zeta;
+
+            // The matrix is scaled by 2^62, and the low 62 bits of the products cancel out. The
+            // Bézout coefficients are divided by 2^62 exactly too, keeping the invariants.
+            (f, g) = (
+                (u as i128 * f + v as i128 * g) >> 62,
+                (q as i128 * f + r as i128 * g) >> 62,
+            );
+            (d, e) = (update_de(u, v, d, e), update_de(q, r, d, e));
+        }
+        debug_assert_eq!(g, 0);
+        debug_assert_eq!(f.abs(), 1);
+
+        // `f = ±1`, so the inverse is `±d`.
+        let negate = f >> 127;
+        let d = (d ^ negate) - negate;
+        Some(Self((d + (Self::ORDER as i128 & (d >> 127))) as u64))
+    }
+}
+
+/// `p^-1 mod 2^62`, where `p` is the order of the Goldilocks field.
+const ORDER_INV_MOD_TWO_POW_62: u64 = 0x1_0000_0001;
+
+/// Returns `(u * d + v * e) / 2^62` modulo the order, in `(-ORDER, ORDER)`, for `d` and `e` in
+/// `(-ORDER, ORDER)` and a transition matrix row `(u, v)` with `|u| + |v| <= 2^62`. Like
+/// `secp256k1_modinv64_update_de_62` of libsecp256k1, this adds the multiple of the order which
+/// makes the sum divisible by `2^62`, without branching.
+#[inline(always)]
+fn update_de(u: i64, v: i64, d: i128, e: i128) -> i128 {
+    const MASK_62: u64 = (1 << 62) - 1;
+    let order = GoldilocksField::ORDER as i128;
+    // Both products are below `2^126` in absolute value, so the sums fit.
+    let sum = u as i128 * d + v as i128 * e;
+    let m = ((sum as u64)
+        .wrapping_mul(ORDER_INV_MOD_TWO_POW_62)
+        .wrapping_neg()
+        & MASK_62) as i128;
+    // Below `2 * ORDER` in absolute value.
+    let x = (sum + m * order) >> 62;
+    // Bring `x` back to `(-ORDER, ORDER)`.
+    let x = x - (order & !((x - order) >> 127));
+    x + (order & ((x + order - 1) >> 127))
+}
+
+/// The number of batches of 59 divsteps needed to invert any element. By Theorem 11.2 of the
+/// safegcd paper, 188 divsteps suffice for 64-bit inputs.
+const SAFEGCD_BATCHES: usize = 188_usize.div_ceil(59);
+
+/// Performs 59 divsteps on the low 64 bits of `f` and `g`, where `zeta = -(delta + 1/2)`, and
+/// returns the updated `zeta` along with the transition matrix `[u, v, q, r]`, scaled by `2^62`,
+/// such that `2^59 * (f', g') = (u * f + v * g, q * f + r * g) / 8`.
+///
+/// This is `secp256k1_modinv64_divsteps_59` from libsecp256k1, which is branch-free.
+#[inline(always)]
+fn divsteps_59(mut zeta: i64, mut f: u64, mut g: u64) -> (i64, [i64; 4]) {
+    // Entries are signed integers in `[-2^62, 2^62]`, represented modulo 2^64 so that they can be
+    // shifted left.
+    let (mut u, mut v, mut q, mut r) = (8u64, 0u64, 0u64, 8u64);
+    for _ in 3..62 {
+        // Masks for `zeta < 0` and for `g` being odd.
+        let mut mask1 = (zeta >> 63) as u64;
+        let mask2 = (g & 1).wrapping_neg();
+        // Conditionally negate `f`, `u` and `v`, then conditionally add them to `g`, `q` and `r`.
+        let x = (f ^ mask1).wrapping_sub(mask1);
+        let y = (u ^ mask1).wrapping_sub(mask1);
+        let z = (v ^ mask1).wrapping_sub(mask1);
+        g = g.wrapping_add(x & mask2);
+        q = q.wrapping_add(y & mask2);
+        r = r.wrapping_add(z & mask2);
+        // If `zeta < 0` and `g` is odd, swap the roles of the rows: `zeta` becomes `-zeta - 2`, and
+        // `g`, `q` and `r` are added to `f`, `u` and `v`. Otherwise, `zeta` becomes `zeta - 1`.
+        mask1 &= mask2;
+        zeta = (zeta ^ mask1 as i64) - 1;
+        f = f.wrapping_add(g & mask1);
+        u = u.wrapping_add(q & mask1);
+        v = v.wrapping_add(r & mask1);
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+    }
+    (zeta, [u as i64, v as i64, q as i64, r as i64])
+}
+
 /// Squares the base N number of times and multiplies the result by the tail value.
 #[inline(always)]
 fn exp_acc<const N: usize>(base: GoldilocksField, tail: GoldilocksField) -> GoldilocksField {
@@ -456,8 +586,64 @@ fn exp_acc<const N: usize>(base: GoldilocksField, tail: GoldilocksField) -> Gold
 
 #[cfg(test)]
 mod tests {
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::types::{Field, Field64, PrimeField64, Sample};
     use crate::{test_field_arithmetic, test_prime_field_arithmetic};
 
     test_prime_field_arithmetic!(crate::goldilocks_field::GoldilocksField);
     test_field_arithmetic!(crate::goldilocks_field::GoldilocksField);
+
+    #[test]
+    fn test_inverse_safegcd() {
+        type F = GoldilocksField;
+
+        assert_eq!(
+            F::ORDER.wrapping_mul(super::ORDER_INV_MOD_TWO_POW_62) & ((1 << 62) - 1),
+            1
+        );
+        assert_eq!(F::ZERO.try_inverse_safegcd(), None);
+
+        let edge_cases = [
+            1,
+            2,
+            3,
+            1 << 32,
+            (1 << 32) - 1,
+            1 << 63,
+            F::ORDER - 2,
+            F::ORDER - 1,
+        ];
+        let random = (0..10_000).map(|_| F::rand().to_canonical_u64());
+        for x in edge_cases.into_iter().chain(random) {
+            let x = F::from_canonical_u64(x);
+            let inverse = x.try_inverse_safegcd().unwrap();
+            assert_eq!(x * inverse, F::ONE);
+            assert_eq!(Some(inverse), x.try_inverse_fermat());
+        }
+
+        // Non-canonical representations.
+        let x = GoldilocksField(F::ORDER + 5);
+        assert_eq!(
+            x.try_inverse_safegcd(),
+            F::from_canonical_u64(5).try_inverse_fermat()
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        type F = GoldilocksField;
+
+        // A non-canonical representation of 1 compares as 1.
+        let one = GoldilocksField(F::ORDER + 1);
+        assert_eq!(one.cmp(&F::ONE), core::cmp::Ordering::Equal);
+        assert!(F::ZERO < one && one < F::TWO);
+        assert!(F::NEG_ONE > F::TWO);
+
+        let mut elements = [F::NEG_ONE, GoldilocksField(F::ORDER), F::TWO, one];
+        elements.sort();
+        assert_eq!(
+            elements.map(|x| x.to_canonical_u64()),
+            [0, 1, 2, F::ORDER - 1]
+        );
+    }
 }
@@ -5,7 +5,7 @@
 #![deny(missing_debug_implementations)]
 #![feature(specialization)]
 #![cfg_attr(target_arch = "x86_64", feature(stdarch_x86_avx512))]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
 extern crate alloc;
 
@@ -22,6 +22,9 @@ pub mod ops;
 pub mod packable;
 pub mod packed;
 pub mod polynomial;
+pub mod power_table;
+#[cfg(any(test, feature = "std"))]
+pub mod root_cache;
 pub mod secp256k1_base;
 pub mod secp256k1_scalar;
 pub mod types;
@@ -1,10 +1,10 @@
 use alloc::vec;
-use alloc::vec::Vec;
 
-use plonky2_util::log2_ceil;
+use plonky2_util::{log2_ceil, log2_strict};
 
-use crate::polynomial::PolynomialCoeffs;
+use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::types::Field;
+use crate::zero_poly_coset::ZeroPolyOnCoset;
 
 impl<F: Field> PolynomialCoeffs<F> {
     /// Polynomial division.
@@ -73,18 +73,63 @@ impl<F: Field> PolynomialCoeffs<F> {
     /// Let `self=p(X)`, this returns `(p(X)-p(z))/(X-z)`.
     /// See <https://en.wikipedia.org/wiki/Horner%27s_method>
     pub fn divide_by_linear(&self, z: F) -> PolynomialCoeffs<F> {
-        let mut bs = self
-            .coeffs
+        self.div_rem_by_linear(z).0
+    }
+
+    /// Returns `(q, p(z))`, the quotient and remainder of the division of `self=p(X)` by `X-z`.
+    pub fn div_rem_by_linear(&self, z: F) -> (PolynomialCoeffs<F>, F) {
+        let Some((&lead, rest)) = self.coeffs.split_last() else {
+            return (Self::empty(), F::ZERO);
+        };
+        // Horner's method, filling the quotient from its leading coefficient.
+        let mut quotient = vec![F::ZERO; rest.len()];
+        let mut acc = lead;
+        for (q, &c) in quotient.iter_mut().zip(rest).rev() {
+            *q = acc;
+            acc = acc * z + c;
+        }
+        (Self { coeffs: quotient }, acc)
+    }
+
+    /// Returns `(q, r)`, the quotient and remainder of the division of `self` by the binomial
+    /// `X^n - c`, in time linear in the length of `self`.
+    pub fn div_rem_by_binomial(&self, n: usize, c: F) -> (Self, Self) {
+        assert_ne!(n, 0, "Division by a constant binomial");
+        let len = self.len();
+        if len <= n {
+            return (Self::empty(), self.clone());
+        }
+
+        // With `self = q * (X^n - c) + r`, `q_j = a_{j+n} + c * q_{j+n}` and `r_j = a_j + c * q_j`.
+        let mut quotient = self.coeffs[n..].to_vec();
+        for j in (0..quotient.len().saturating_sub(n)).rev() {
+            let q = quotient[j + n];
+            quotient[j] += c * q;
+        }
+        let remainder = self.coeffs[..n]
             .iter()
-            .rev()
-            .scan(F::ZERO, |acc, &c| {
-                *acc = *acc * z + c;
-                Some(*acc)
-            })
-            .collect::<Vec<_>>();
-        bs.pop();
-        bs.reverse();
-        Self { coeffs: bs }
+            .zip(quotient.iter().map(Some).chain(core::iter::repeat(None)))
+            .map(|(&a, q)| q.map_or(a, |&q| a + c * q))
+            .collect();
+        (Self { coeffs: quotient }, Self { coeffs: remainder })
+    }
+
+    /// Returns the quotient of `self` by the vanishing polynomial `Z_H(X) = X^n - 1` of a subgroup
+    /// `H` of order `n`. Panics if `Z_H` does not divide `self`, i.e. if `self` does not vanish
+    /// on `H`.
+    pub fn divide_by_vanishing_poly(&self, n: usize) -> Self {
+        let (quotient, remainder) = self.div_rem_by_vanishing_poly(n);
+        assert!(
+            remainder.coeffs.iter().all(|c| c.is_zero()),
+            "The polynomial does not vanish on the subgroup"
+        );
+        quotient
+    }
+
+    /// Returns `(q, r)`, the quotient and remainder of the division of `self` by the vanishing
+    /// polynomial `Z_H(X) = X^n - 1` of a subgroup `H` of order `n`.
+    pub fn div_rem_by_vanishing_poly(&self, n: usize) -> (Self, Self) {
+        self.div_rem_by_binomial(n, F::ONE)
     }
 
     /// Computes the inverse of `self` modulo `x^n`.
@@ -121,10 +166,9 @@ impl<F: Field> PolynomialCoeffs<F> {
             tmp.coeffs.iter_mut().for_each(|x| *x = -(*x));
             tmp.trim();
             let mut b = &a * &tmp;
-            b.trim();
-            if b.len() > l {
-                b.coeffs.drain(l..);
-            }
+            // Exactly `l` coefficients, including trailing zeros, so that the next ones land at
+            // the right degree.
+            b.coeffs.resize(l, F::ZERO);
             a.coeffs.extend_from_slice(&b.coeffs);
         }
         a.coeffs.drain(n..);
@@ -132,6 +176,25 @@ impl<F: Field> PolynomialCoeffs<F> {
     }
 }
 
+impl<F: Field> PolynomialValues<F> {
+    /// Divides, pointwise, the evaluations `self` of a polynomial on the coset `gK` by those of the
+    /// vanishing polynomial `Z_H(X) = X^n - 1`, with `n = 2^n_log`, `H <= K` and `g` the
+    /// [`coset_shift`](Field::coset_shift). If `Z_H` divides the polynomial, the result holds the
+    /// evaluations of the quotient on `gK`.
+    ///
+    /// `Z_H` takes only `|K|/|H|` distinct values on `gK`, so this only inverts that many values.
+    pub fn divide_by_vanishing_poly_on_coset(mut self, n_log: usize) -> Self {
+        let lg_k = log2_strict(self.len());
+        assert!(n_log <= lg_k, "The subgroup H must be contained in K");
+        let z_h_on_coset = ZeroPolyOnCoset::<F>::new(n_log, lg_k - n_log);
+        self.values
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v *= z_h_on_coset.eval_inverse(i));
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::rngs::OsRng;
@@ -156,4 +219,71 @@ mod tests {
             &(&quotient * &vec![-z, F::ONE].into()) + &vec![ev].into() // `quotient * (X-z) + ev`
         );
     }
+
+    #[test]
+    fn test_div_rem_by_linear() {
+        type F = GoldilocksField;
+        for n in [0, 1, 2, 17] {
+            let poly = PolynomialCoeffs::new(F::rand_vec(n));
+            let z = F::rand();
+            let (quotient, remainder) = poly.div_rem_by_linear(z);
+            assert_eq!(remainder, poly.eval(z));
+            assert_eq!(
+                poly,
+                &(&quotient * &vec![-z, F::ONE].into()) + &vec![remainder].into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_div_rem_by_binomial() {
+        type F = GoldilocksField;
+        for (len, n) in [(0, 4), (3, 4), (4, 4), (5, 4), (13, 4), (64, 16), (100, 1)] {
+            let poly = PolynomialCoeffs::new(F::rand_vec(len));
+            let c = F::rand();
+            let (quotient, remainder) = poly.div_rem_by_binomial(n, c);
+            assert!(remainder.len() <= n);
+
+            let mut binomial = PolynomialCoeffs::zero(n + 1);
+            binomial.coeffs[0] = -c;
+            binomial.coeffs[n] = F::ONE;
+            assert_eq!(poly, &(&quotient * &binomial) + &remainder);
+            assert_eq!(poly.div_rem(&binomial), (quotient, remainder));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not vanish")]
+    fn test_divide_by_vanishing_poly_with_remainder() {
+        type F = GoldilocksField;
+
+        let poly = PolynomialCoeffs::new(vec![F::ONE; 8]);
+        poly.divide_by_vanishing_poly(4);
+    }
+
+    #[test]
+    fn test_divide_by_vanishing_poly_on_coset() {
+        type F = GoldilocksField;
+        const N_LOG: usize = 4;
+        const RATE_BITS: usize = 2;
+
+        // `p = q * Z_H` with `Z_H = X^16 - 1`.
+        let quotient = PolynomialCoeffs::new(F::rand_vec(1 << N_LOG));
+        let mut z_h = PolynomialCoeffs::zero((1 << N_LOG) + 1);
+        z_h.coeffs[0] = F::NEG_ONE;
+        z_h.coeffs[1 << N_LOG] = F::ONE;
+        let poly = &quotient * &z_h;
+        assert_eq!(
+            poly.div_rem_by_vanishing_poly(1 << N_LOG),
+            (quotient.clone(), PolynomialCoeffs::zero(1 << N_LOG))
+        );
+        assert_eq!(poly.divide_by_vanishing_poly(1 << N_LOG), quotient);
+
+        let lde_len = 1 << (N_LOG + RATE_BITS);
+        let values = poly.padded(lde_len).coset_fft(F::coset_shift());
+        assert_eq!(
+            values.divide_by_vanishing_poly_on_coset(N_LOG),
+            quotient.padded(lde_len).coset_fft(F::coset_shift())
+        );
+    }
 }
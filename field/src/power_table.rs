@@ -0,0 +1,106 @@
+use alloc::vec::Vec;
+
+use crate::types::{Field, Powers};
+
+/// A table of precomputed powers of a fixed base, for computing arbitrary powers of it with a few
+/// multiplications.
+///
+/// Exponents are split into windows of `window_bits` bits. For each window `i`, the table holds
+/// `base^(d * 2^(i * window_bits))` for every digit `d`, so that `base^e` is the product of one
+/// entry per window of `e`. Powers of exponents below `2^exponent_bits` then cost
+/// `exponent_bits / window_bits` multiplications, rather than the squarings and multiplications of
+/// [`Field::exp_u64`].
+#[derive(Clone, Debug)]
+pub struct PowerTable<F: Field> {
+    base: F,
+    window_bits: usize,
+    exponent_bits: usize,
+    windows: Vec<Vec<F>>,
+}
+
+impl<F: Field> PowerTable<F> {
+    /// Precomputes the powers of `base` needed to raise it to any exponent below
+    /// `2^exponent_bits`, using windows of `window_bits` bits.
+    pub fn new(base: F, window_bits: usize, exponent_bits: usize) -> Self {
+        assert!(
+            (1..=16).contains(&window_bits),
+            "window_bits must be between 1 and 16"
+        );
+        assert!(exponent_bits <= 64, "exponent_bits must be at most 64");
+
+        let num_windows = exponent_bits.div_ceil(window_bits);
+        let mut windows = Vec::with_capacity(num_windows);
+        let mut window_base = base;
+        for _ in 0..num_windows {
+            let window = window_base
+                .powers()
+                .take(1 << window_bits)
+                .collect::<Vec<_>>();
+            window_base = window[window.len() - 1] * window_base;
+            windows.push(window);
+        }
+
+        Self {
+            base,
+            window_bits,
+            exponent_bits,
+            windows,
+        }
+    }
+
+    /// The base whose powers are tabulated.
+    pub fn base(&self) -> F {
+        self.base
+    }
+
+    /// Returns `base^exponent`. The exponent must be below `2^exponent_bits`.
+    pub fn exp_u64(&self, exponent: u64) -> F {
+        debug_assert!(
+            self.exponent_bits == 64 || exponent >> self.exponent_bits == 0,
+            "exponent out of the range of the table"
+        );
+        let mask = (1 << self.window_bits) - 1;
+        self.windows
+            .iter()
+            .enumerate()
+            .map(|(i, window)| window[((exponent >> (i * self.window_bits)) & mask) as usize])
+            .fold(F::ONE, |acc, power| acc * power)
+    }
+
+    /// Returns the powers of the base starting at `base^start`, i.e. `base^start`,
+    /// `base^(start + 1)`, ...
+    pub fn powers_from(&self, start: u64) -> Powers<F> {
+        self.base.shifted_powers(self.exp_u64(start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::power_table::PowerTable;
+    use crate::types::{Field, Sample};
+
+    #[test]
+    fn test_power_table() {
+        type F = GoldilocksField;
+
+        let base = F::rand();
+        for (window_bits, exponent_bits) in [(1, 64), (4, 64), (5, 20), (8, 16), (16, 64)] {
+            let table = PowerTable::new(base, window_bits, exponent_bits);
+            let max = if exponent_bits == 64 {
+                u64::MAX
+            } else {
+                (1 << exponent_bits) - 1
+            };
+            for exponent in [0, 1, 2, 31, 32, 1000, max - 1, max] {
+                assert_eq!(table.exp_u64(exponent), base.exp_u64(exponent));
+            }
+        }
+
+        let table = PowerTable::new(base, 4, 16);
+        assert_eq!(
+            table.powers_from(100).take(10).collect::<Vec<_>>(),
+            base.powers().skip(100).take(10).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! A global, thread-safe cache of two-adic subgroups, FFT root tables and other tables which only
+//! depend on the size of a domain, such as the LDEs of Lagrange selectors.
+//!
+//! Tables are keyed by field and size, and shared through [`Arc`]s, so that repeated FFTs and
+//! proofs of the same size compute each table once. [`fft`](crate::fft::fft) and the other
+//! transforms of [`crate::fft`] use the cached root table when they aren't given one.
+//!
+//! Cached tables are never dropped implicitly: a table of size `n` holds about `n` field elements,
+//! so long-running processes going through many sizes should call [`evict_cached_roots`] or
+//! [`clear_root_cache`] once they are done with a size.
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use std::sync::RwLock;
+
+use crate::fft::{fft_root_table, FftRootTable};
+use crate::polynomial::PolynomialValues;
+use crate::types::Field;
+
+/// The kinds of tables held in the cache.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum TableKind {
+    TwoAdicSubgroup,
+    FftRootTable,
+    /// Indexed by the blowup of the LDE, in bits.
+    LagrangeSelectorsOnCoset(usize),
+}
+
+type CacheKey = (TypeId, TableKind, usize);
+
+static CACHE: RwLock<BTreeMap<CacheKey, Arc<dyn Any + Send + Sync>>> = RwLock::new(BTreeMap::new());
+
+/// Returns the table of the given kind and size for `F`, computing it with `compute` and caching
+/// it if it isn't cached yet.
+fn get_or_compute<F: Field, T: Any + Send + Sync>(
+    kind: TableKind,
+    lg_n: usize,
+    compute: impl FnOnce() -> T,
+) -> Arc<T> {
+    let key = (TypeId::of::<F>(), kind, lg_n);
+    let cached = CACHE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+        .cloned();
+    let table = cached.unwrap_or_else(|| {
+        // Computed without holding the lock, so that other tables remain available meanwhile. If
+        // another thread caches the same table first, its table is kept.
+        let table: Arc<dyn Any + Send + Sync> = Arc::new(compute());
+        CACHE
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert(table)
+            .clone()
+    });
+    table
+        .downcast()
+        .expect("cached tables are keyed by their type")
+}
+
+/// Returns the subgroup of order `2^lg_n` of `F`, as computed by [`Field::two_adic_subgroup`].
+pub fn cached_two_adic_subgroup<F: Field>(lg_n: usize) -> Arc<Vec<F>> {
+    get_or_compute::<F, _>(TableKind::TwoAdicSubgroup, lg_n, || {
+        F::two_adic_subgroup(lg_n)
+    })
+}
+
+/// Returns the root table for FFTs of size `2^lg_n` over `F`, as computed by [`fft_root_table`].
+pub fn cached_fft_root_table<F: Field>(lg_n: usize) -> Arc<FftRootTable<F>> {
+    get_or_compute::<F, _>(TableKind::FftRootTable, lg_n, || fft_root_table(1 << lg_n))
+}
+
+/// Returns the evaluations of the first and last Lagrange selectors of the subgroup of order
+/// `2^lg_n` on the coset of order `2^(lg_n + rate_bits)` shifted by
+/// [`coset_shift`](Field::coset_shift), as computed by [`PolynomialValues::lde_onto_coset`].
+pub fn cached_lagrange_selectors_on_coset<F: Field>(
+    lg_n: usize,
+    rate_bits: usize,
+) -> Arc<[PolynomialValues<F>; 2]> {
+    get_or_compute::<F, _>(TableKind::LagrangeSelectorsOnCoset(rate_bits), lg_n, || {
+        let n = 1 << lg_n;
+        [0, n - 1].map(|i| PolynomialValues::selector(n, i).lde_onto_coset(rate_bits))
+    })
+}
+
+/// Removes the cached tables of size `2^lg_n` for `F`, including the LDEs of that size for any
+/// blowup. Tables still in use stay alive until their last [`Arc`] is dropped.
+pub fn evict_cached_roots<F: Field>(lg_n: usize) {
+    let type_id = TypeId::of::<F>();
+    CACHE
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|&(key_type_id, _, key_lg_n), _| key_type_id != type_id || key_lg_n != lg_n);
+}
+
+/// Removes all the cached tables, for every field and size.
+pub fn clear_root_cache() {
+    CACHE.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use crate::extension::quadratic::QuadraticExtension;
+    use crate::fft::fft_root_table;
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::polynomial::PolynomialValues;
+    use crate::root_cache::{
+        cached_fft_root_table, cached_lagrange_selectors_on_coset, cached_two_adic_subgroup,
+        evict_cached_roots,
+    };
+    use crate::types::Field;
+
+    #[test]
+    fn test_root_cache() {
+        type F = GoldilocksField;
+        // A size unused by other tests, which may share the cache concurrently.
+        const LG_N: usize = 13;
+
+        let subgroup = cached_two_adic_subgroup::<F>(LG_N);
+        assert_eq!(*subgroup, F::two_adic_subgroup(LG_N));
+        assert!(Arc::ptr_eq(&subgroup, &cached_two_adic_subgroup::<F>(LG_N)));
+
+        let root_table = cached_fft_root_table::<F>(LG_N);
+        assert_eq!(*root_table, fft_root_table::<F>(1 << LG_N));
+        assert!(Arc::ptr_eq(&root_table, &cached_fft_root_table::<F>(LG_N)));
+
+        // Tables of other fields are kept apart.
+        type F2 = QuadraticExtension<F>;
+        assert_eq!(
+            *cached_two_adic_subgroup::<F2>(LG_N),
+            F2::two_adic_subgroup(LG_N)
+        );
+
+        let selectors = cached_lagrange_selectors_on_coset::<F>(LG_N, 2);
+        assert_eq!(
+            selectors[1],
+            PolynomialValues::selector(1 << LG_N, (1 << LG_N) - 1).lde_onto_coset(2)
+        );
+        assert!(Arc::ptr_eq(
+            &selectors,
+            &cached_lagrange_selectors_on_coset::<F>(LG_N, 2)
+        ));
+        assert!(!Arc::ptr_eq(
+            &selectors,
+            &cached_lagrange_selectors_on_coset::<F>(LG_N, 1)
+        ));
+
+        evict_cached_roots::<F>(LG_N);
+        let recomputed = cached_two_adic_subgroup::<F>(LG_N);
+        assert!(!Arc::ptr_eq(&subgroup, &recomputed));
+        assert_eq!(subgroup, recomputed);
+        assert!(!Arc::ptr_eq(
+            &selectors,
+            &cached_lagrange_selectors_on_coset::<F>(LG_N, 2)
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::fmt::{Debug, Display};
 use core::hash::Hash;
 use core::iter::{Product, Sum};
@@ -564,6 +565,34 @@ pub trait PrimeField64: PrimeField + Field64 {
     fn to_canonical(&self) -> Self {
         Self::from_canonical_u64(self.to_canonical_u64())
     }
+
+    /// Compares two elements by their canonical value in `[0, p)`.
+    #[inline]
+    fn cmp_canonical(&self, other: &Self) -> Ordering {
+        self.to_canonical_u64().cmp(&other.to_canonical_u64())
+    }
+}
+
+/// Compares two vectors of limbs lexicographically by their canonical values, the first limb being
+/// the most significant one, as for tuples such as `(context, segment, virtual_address)`. As for
+/// slices, a vector is smaller than any longer vector it is a prefix of.
+pub fn cmp_limbs<F: PrimeField64>(a: &[F], b: &[F]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.cmp_canonical(y))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// Compares two numbers given as little-endian vectors of limbs, the first limb being the least
+/// significant one. Missing limbs of the shorter vector are treated as zeros.
+pub fn cmp_limbs_le<F: PrimeField64>(a: &[F], b: &[F]) -> Ordering {
+    let limb = |limbs: &[F], i: usize| limbs.get(i).map_or(0, F::to_canonical_u64);
+    (0..a.len().max(b.len()))
+        .rev()
+        .map(|i| limb(a, i).cmp(&limb(b, i)))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
 }
 
 /// An iterator over the powers of a certain base element `b`: `b^0, b^1, b^2, ...`.
@@ -614,13 +643,71 @@ impl<F: Field> Powers<F> {
             current: current.repeated_frobenius(k),
         }
     }
+
+    /// Splits the powers into consecutive chunks of `chunk_size` powers, e.g. to consume them in
+    /// parallel by zipping the chunks with chunks of a slice. Each chunk is started with a single
+    /// multiplication, rather than by running through the powers of the previous chunks.
+    pub fn chunks(self, chunk_size: usize) -> PowersChunks<F> {
+        assert_ne!(chunk_size, 0, "chunk_size must be non-zero");
+        PowersChunks {
+            chunk_size,
+            chunk_base: self.base.exp_u64(chunk_size as u64),
+            powers: self,
+        }
+    }
+}
+
+/// An iterator over consecutive chunks of [`Powers`], as returned by [`Powers::chunks`].
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+#[derive(Clone, Debug)]
+pub struct PowersChunks<F: Field> {
+    chunk_size: usize,
+    /// `base^chunk_size`.
+    chunk_base: F,
+    /// The powers of the next chunk.
+    powers: Powers<F>,
+}
+
+impl<F: Field> Iterator for PowersChunks<F> {
+    type Item = core::iter::Take<Powers<F>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.powers.clone().take(self.chunk_size);
+        self.powers.current *= self.chunk_base;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Field;
+    use core::cmp::Ordering;
+
+    use super::{cmp_limbs, cmp_limbs_le, Field};
     use crate::goldilocks_field::GoldilocksField;
 
+    #[test]
+    fn test_powers_chunks() {
+        type F = GoldilocksField;
+
+        let base = F::from_canonical_u64(7);
+        let start = F::from_canonical_u64(3);
+        let expected: Vec<F> = base.shifted_powers(start).take(20).collect();
+        for chunk_size in [1, 3, 7, 20] {
+            let chunks: Vec<Vec<F>> = base
+                .shifted_powers(start)
+                .chunks(chunk_size)
+                .take(20usize.div_ceil(chunk_size))
+                .map(Iterator::collect)
+                .collect();
+            assert!(chunks.iter().all(|chunk| chunk.len() == chunk_size));
+            assert_eq!(chunks.concat()[..20], expected[..]);
+        }
+    }
+
     #[test]
     fn test_powers_nth() {
         type F = GoldilocksField;
@@ -637,4 +724,35 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_cmp_limbs() {
+        type F = GoldilocksField;
+        let limbs = |values: &[u64]| {
+            values
+                .iter()
+                .map(|&v| F::from_canonical_u64(v))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(cmp_limbs(&limbs(&[1, 5]), &limbs(&[2, 0])), Ordering::Less);
+        assert_eq!(
+            cmp_limbs(&limbs(&[2, 5]), &limbs(&[2, 3])),
+            Ordering::Greater
+        );
+        assert_eq!(cmp_limbs(&limbs(&[2, 3]), &limbs(&[2, 3])), Ordering::Equal);
+        assert_eq!(cmp_limbs(&limbs(&[2]), &limbs(&[2, 0])), Ordering::Less);
+        assert_eq!(cmp_limbs(&[F::NEG_ONE], &[F::ZERO]), Ordering::Greater);
+
+        assert_eq!(
+            cmp_limbs_le(&limbs(&[1, 5]), &limbs(&[2, 0])),
+            Ordering::Greater
+        );
+        assert_eq!(
+            cmp_limbs_le(&limbs(&[7, 2]), &limbs(&[3, 2])),
+            Ordering::Greater
+        );
+        assert_eq!(cmp_limbs_le(&limbs(&[2]), &limbs(&[2, 0])), Ordering::Equal);
+        assert_eq!(cmp_limbs_le(&limbs(&[2]), &limbs(&[0, 1])), Ordering::Less);
+    }
 }
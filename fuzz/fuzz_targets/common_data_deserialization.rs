@@ -0,0 +1,12 @@
+//! Arbitrary bytes deserialized as common circuit data.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::DefaultGateSerializer;
+use plonky2_fuzz::{D, F};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = CommonCircuitData::<F, D>::from_bytes(data.to_vec(), &DefaultGateSerializer);
+});
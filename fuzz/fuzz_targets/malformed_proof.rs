@@ -0,0 +1,25 @@
+//! A valid proof, corrupted by XOR-ing the fuzzer's input into its serialization, so that most
+//! inputs reach the verifier. The verifier must reject every corruption changing the proof.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2_fuzz::{fixture, C, D, F};
+
+fuzz_target!(|data: &[u8]| {
+    let fixture = fixture();
+    let mut bytes = fixture.proof_bytes.clone();
+    for (byte, mask) in bytes.iter_mut().zip(data) {
+        *byte ^= mask;
+    }
+    if let Ok(proof) =
+        ProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &fixture.verifier_data.common)
+    {
+        let is_valid = fixture.verifier_data.verify(proof.clone()).is_ok();
+        assert!(
+            !is_valid || proof == fixture.proof,
+            "accepted a corrupted proof"
+        );
+    }
+});
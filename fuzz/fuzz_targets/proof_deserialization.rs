@@ -0,0 +1,16 @@
+//! Arbitrary bytes deserialized as a proof, which is then verified.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2_fuzz::{fixture, C, D, F};
+
+fuzz_target!(|data: &[u8]| {
+    let fixture = fixture();
+    let common = &fixture.verifier_data.common;
+    if let Ok(proof) = ProofWithPublicInputs::<F, C, D>::from_bytes(data.to_vec(), common) {
+        let _ = fixture.verifier_data.verify(proof);
+    }
+    let _ = ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(data, common);
+});
@@ -0,0 +1,21 @@
+//! Arbitrary bytes deserialized as verifier data, which is then used to verify a proof.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::circuit_data::VerifierCircuitData;
+use plonky2::util::serialization::DefaultGateSerializer;
+use plonky2_fuzz::{fixture, C, D, F};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = VerifierCircuitData::<F, C, D>::from_versioned_bytes(data, &DefaultGateSerializer);
+    let Ok(verifier_data) =
+        VerifierCircuitData::<F, C, D>::from_bytes(data.to_vec(), &DefaultGateSerializer)
+    else {
+        return;
+    };
+    // Only verify against verifier data with the shape the proof was made for.
+    if verifier_data.common == fixture().verifier_data.common {
+        let _ = verifier_data.verify(fixture().proof.clone());
+    }
+});
@@ -0,0 +1,46 @@
+//! Shared fixtures of the fuzz targets: the verifier data of a small circuit, and a valid proof
+//! for it, taken from the golden files of the versioned serialization format.
+
+use std::sync::OnceLock;
+
+use plonky2::plonk::circuit_data::VerifierCircuitData;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::util::serialization::DefaultGateSerializer;
+
+pub const D: usize = 2;
+pub type C = PoseidonGoldilocksConfig;
+pub type F = <C as GenericConfig<D>>::F;
+
+pub struct Fixture {
+    pub verifier_data: VerifierCircuitData<F, C, D>,
+    pub proof: ProofWithPublicInputs<F, C, D>,
+    /// The serialization of `proof`.
+    pub proof_bytes: Vec<u8>,
+}
+
+pub fn fixture() -> &'static Fixture {
+    static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+    FIXTURE.get_or_init(|| {
+        let verifier_data = VerifierCircuitData::from_versioned_bytes(
+            include_bytes!(
+                "../../plonky2/src/util/serialization/testdata/verifier_circuit_data_v1.bin"
+            ),
+            &DefaultGateSerializer,
+        )
+        .expect("valid verifier data");
+        let proof = ProofWithPublicInputs::from_versioned_bytes(
+            include_bytes!(
+                "../../plonky2/src/util/serialization/testdata/proof_with_public_inputs_v1.bin"
+            ),
+            &verifier_data.common,
+        )
+        .expect("valid proof");
+        let proof_bytes = proof.to_bytes();
+        Fixture {
+            verifier_data,
+            proof,
+            proof_bytes,
+        }
+    })
+}
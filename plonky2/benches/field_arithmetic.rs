@@ -5,7 +5,7 @@ use plonky2::field::extension::quadratic::QuadraticExtension;
 use plonky2::field::extension::quartic::QuarticExtension;
 use plonky2::field::extension::quintic::QuinticExtension;
 use plonky2::field::goldilocks_field::GoldilocksField;
-use plonky2::field::types::Field;
+use plonky2::field::types::{Field, Sample};
 use tynm::type_name;
 
 pub(crate) fn bench_field<F: Field>(c: &mut Criterion) {
@@ -171,8 +171,21 @@ pub(crate) fn bench_field<F: Field>(c: &mut Criterion) {
     );
 }
 
+fn bench_goldilocks_inverse(c: &mut Criterion) {
+    type F = GoldilocksField;
+
+    c.bench_function("try_inverse_fermat<GoldilocksField>", |b| {
+        b.iter_batched(F::rand, |x| x.try_inverse_fermat(), BatchSize::SmallInput)
+    });
+
+    c.bench_function("try_inverse_safegcd<GoldilocksField>", |b| {
+        b.iter_batched(F::rand, |x| x.try_inverse_safegcd(), BatchSize::SmallInput)
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_field::<GoldilocksField>(c);
+    bench_goldilocks_inverse(c);
     bench_field::<QuadraticExtension<GoldilocksField>>(c);
     bench_field::<QuarticExtension<GoldilocksField>>(c);
     bench_field::<QuinticExtension<GoldilocksField>>(c);
@@ -0,0 +1,61 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::field::types::Field;
+use tynm::type_name;
+
+pub(crate) fn bench_divide_by_linear<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("divide_by_linear<{}>", type_name::<F>()));
+
+    for size_log in [13, 14, 15, 16] {
+        let size = 1 << size_log;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            let poly = PolynomialCoeffs::new(F::rand_vec(size));
+            let z = F::rand();
+            b.iter(|| poly.divide_by_linear(z));
+        });
+    }
+}
+
+pub(crate) fn bench_divide_by_vanishing_poly<F: Field>(c: &mut Criterion) {
+    const QUOTIENT_DEGREE_BITS: usize = 3;
+
+    let mut group = c.benchmark_group(format!("divide_by_vanishing_poly<{}>", type_name::<F>()));
+
+    for n_log in [13, 14, 15, 16] {
+        let n = 1 << n_log;
+        let poly = PolynomialCoeffs::new(F::rand_vec(n << QUOTIENT_DEGREE_BITS));
+
+        group.bench_with_input(BenchmarkId::new("coeffs", n), &n, |b, _| {
+            b.iter(|| poly.div_rem_by_vanishing_poly(n));
+        });
+        group.bench_with_input(BenchmarkId::new("coset_values", n), &n, |b, _| {
+            let values = poly.coset_fft(F::coset_shift());
+            b.iter(|| values.clone().divide_by_vanishing_poly_on_coset(n_log));
+        });
+    }
+}
+
+pub(crate) fn bench_div_rem<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("div_rem<{}>", type_name::<F>()));
+
+    for size_log in [10, 12, 14] {
+        let size = 1 << size_log;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            let a = PolynomialCoeffs::new(F::rand_vec(size));
+            let divisor = PolynomialCoeffs::new(F::rand_vec(size / 2));
+            b.iter(|| a.div_rem(&divisor));
+        });
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_divide_by_linear::<GoldilocksField>(c);
+    bench_divide_by_vanishing_poly::<GoldilocksField>(c);
+    bench_div_rem::<GoldilocksField>(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::types::Field;
+use plonky2::gates::noop::NoopGate;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use tynm::type_name;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Builds a circuit with `2^degree_bits` rows, proving the knowledge of a Poseidon preimage.
+fn circuit(degree_bits: usize) -> (CircuitData<F, C, D>, PartialWitness<F>) {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    let preimage = builder.add_virtual_targets(4);
+    let hash =
+        builder.hash_n_to_hash_no_pad::<<C as GenericConfig<D>>::InnerHasher>(preimage.clone());
+    builder.register_public_inputs(&hash.elements);
+    // Fill the circuit up to just over half of its target size, so that padding rounds it up to
+    // `2^degree_bits` rows.
+    while builder.num_gates() < (1 << (degree_bits - 1)) + 1 {
+        builder.add_gate(NoopGate, vec![]);
+    }
+    let data = builder.build::<C>();
+    assert_eq!(data.common.degree_bits(), degree_bits);
+
+    let mut pw = PartialWitness::new();
+    for (i, &t) in preimage.iter().enumerate() {
+        pw.set_target(t, F::from_canonical_usize(i)).unwrap();
+    }
+    (data, pw)
+}
+
+pub(crate) fn bench_prove(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("prove<{}>", type_name::<C>()));
+    group.sample_size(10);
+
+    // Small and medium circuits.
+    for degree_bits in [12, 14] {
+        let (data, pw) = circuit(degree_bits);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(1 << degree_bits),
+            &degree_bits,
+            |b, _| b.iter(|| data.prove(pw.clone()).unwrap()),
+        );
+    }
+}
+
+pub(crate) fn bench_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("verify<{}>", type_name::<C>()));
+
+    for degree_bits in [12, 14] {
+        let (data, pw) = circuit(degree_bits);
+        let proof = data.prove(pw).unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(1 << degree_bits),
+            &degree_bits,
+            |b, _| b.iter(|| data.verify(proof.clone()).unwrap()),
+        );
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_prove(c);
+    bench_verify(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
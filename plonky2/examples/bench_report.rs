@@ -0,0 +1,129 @@
+// Summarizes the results of `cargo bench` as JSON, so that they can be stored as a baseline and
+// compared across commits or machines:
+//
+//     cargo bench -p plonky2
+//     cargo run --release --example bench_report -- --output baseline.json
+//     # ... change things, run the benchmarks again ...
+//     cargo run --release --example bench_report -- --baseline baseline.json --max-ratio 1.1
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use structopt::StructOpt;
+
+#[derive(Clone, StructOpt, Debug)]
+#[structopt(name = "bench_report")]
+struct Options {
+    /// Directory in which criterion stores its results.
+    #[structopt(long, parse(from_os_str), default_value = "target/criterion")]
+    criterion_dir: PathBuf,
+
+    /// Writes the report to this file instead of the standard output.
+    #[structopt(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// A report to compare the results against.
+    #[structopt(long, parse(from_os_str))]
+    baseline: Option<PathBuf>,
+
+    /// Fails if a benchmark is slower than its baseline by more than this ratio.
+    #[structopt(long, requires = "baseline")]
+    max_ratio: Option<f64>,
+}
+
+/// The results of a benchmark.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BenchResult {
+    /// Mean time per iteration, in nanoseconds.
+    mean_ns: f64,
+    /// Standard deviation of the time per iteration, in nanoseconds.
+    std_dev_ns: f64,
+    /// Mean time per iteration of the baseline, if any.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    baseline_mean_ns: Option<f64>,
+    /// `mean_ns / baseline_mean_ns`, if there is a baseline.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    ratio: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Report {
+    /// Results by benchmark ID, e.g. `fft<GoldilocksField>/8192`.
+    benchmarks: BTreeMap<String, BenchResult>,
+}
+
+/// Collects the results of all the benchmarks under `dir`, which criterion stores in a
+/// `new/benchmark.json` and a `new/estimates.json` file per benchmark.
+fn collect(dir: &Path, report: &mut Report) -> Result<()> {
+    let new_dir = dir.join("new");
+    if new_dir.join("estimates.json").is_file() {
+        let benchmark: Value = serde_json::from_slice(&fs::read(new_dir.join("benchmark.json"))?)?;
+        let estimates: Value = serde_json::from_slice(&fs::read(new_dir.join("estimates.json"))?)?;
+        let id = benchmark["full_id"]
+            .as_str()
+            .with_context(|| format!("no benchmark ID in {:?}", new_dir))?;
+        let mean_ns = estimates["mean"]["point_estimate"].as_f64();
+        let std_dev_ns = estimates["std_dev"]["point_estimate"].as_f64();
+        let (Some(mean_ns), Some(std_dev_ns)) = (mean_ns, std_dev_ns) else {
+            bail!("malformed estimates in {:?}", new_dir);
+        };
+        report.benchmarks.insert(
+            id.to_string(),
+            BenchResult {
+                mean_ns,
+                std_dev_ns,
+                baseline_mean_ns: None,
+                ratio: None,
+            },
+        );
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        // Criterion's HTML reports don't contain results.
+        if path.is_dir() && path.file_name().is_none_or(|name| name != "report") {
+            collect(&path, report)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let options = Options::from_args_safe()?;
+
+    let mut report = Report::default();
+    collect(&options.criterion_dir, &mut report)
+        .with_context(|| format!("reading {:?}", options.criterion_dir))?;
+
+    let mut regressions = Vec::new();
+    if let Some(baseline_path) = &options.baseline {
+        let baseline: Report = serde_json::from_slice(&fs::read(baseline_path)?)?;
+        for (id, result) in report.benchmarks.iter_mut() {
+            let Some(baseline_result) = baseline.benchmarks.get(id) else {
+                continue;
+            };
+            let ratio = result.mean_ns / baseline_result.mean_ns;
+            result.baseline_mean_ns = Some(baseline_result.mean_ns);
+            result.ratio = Some(ratio);
+            if options.max_ratio.is_some_and(|max_ratio| ratio > max_ratio) {
+                regressions.push(format!("{}: {:.3}x", id, ratio));
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match &options.output {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+
+    if !regressions.is_empty() {
+        bail!("benchmarks regressed: {}", regressions.join(", "));
+    }
+    Ok(())
+}
@@ -1,6 +1,9 @@
 #[cfg(not(feature = "std"))]
 use alloc::{format, vec::Vec};
+#[cfg(all(feature = "mmap", unix))]
+use std::sync::Arc;
 
+use anyhow::{ensure, Result};
 use itertools::Itertools;
 use plonky2_field::types::Field;
 use plonky2_maybe_rayon::*;
@@ -14,10 +17,17 @@ use crate::fri::prover::fri_proof;
 use crate::fri::structure::{FriBatchInfo, FriInstanceInfo};
 use crate::fri::FriParams;
 use crate::hash::hash_types::RichField;
+#[cfg(all(feature = "mmap", unix))]
+use crate::hash::merkle_tree::MerkleLeaves;
 use crate::hash::merkle_tree::MerkleTree;
 use crate::iop::challenger::Challenger;
 use crate::plonk::config::GenericConfig;
+#[cfg(all(feature = "mmap", unix))]
+use crate::plonk::config::Hasher;
 use crate::timed;
+use crate::util::column_storage::ColumnStorage;
+#[cfg(all(feature = "mmap", unix))]
+use crate::util::mmap::MmapVec;
 use crate::util::reducing::ReducingFactor;
 use crate::util::timing::TimingTree;
 use crate::util::{log2_strict, reverse_bits, reverse_index_bits_in_place, transpose};
@@ -111,6 +121,236 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         }
     }
 
+    /// Like [`Self::from_values`], but keeps the LDEs and the Merkle leaves in `storage`; see
+    /// [`Self::from_coeffs_with_storage`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_values_with_storage(
+        values: Vec<PolynomialValues<F>>,
+        rate_bits: usize,
+        blinding: bool,
+        cap_height: usize,
+        timing: &mut TimingTree,
+        fft_root_table: Option<&FftRootTable<F>>,
+        storage: &ColumnStorage,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        ensure!(!values.is_empty(), "No polynomials to commit to");
+        let coeffs = timed!(
+            timing,
+            "IFFT",
+            values.into_par_iter().map(|v| v.ifft()).collect::<Vec<_>>()
+        );
+
+        Self::from_coeffs_with_storage(
+            coeffs,
+            rate_bits,
+            blinding,
+            cap_height,
+            timing,
+            fft_root_table,
+            storage,
+            chunk_size,
+        )
+    }
+
+    /// Like [`Self::from_coeffs`], but keeps the LDEs and the Merkle leaves in `storage`.
+    ///
+    /// With [`ColumnStorage::Mapped`], the LDE of each polynomial is computed in a mapped column.
+    /// The columns are then transposed into mapped Merkle leaves `chunk_size` rows at a time, each
+    /// leaf being hashed as it is written, so that only the coefficients and the digests of the
+    /// Merkle tree are held in memory. With [`ColumnStorage::Memory`], this is
+    /// [`Self::from_coeffs`].
+    ///
+    /// Fails if `polynomials` is empty, if `chunk_size` is zero or if a column cannot be allocated.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_coeffs_with_storage(
+        polynomials: Vec<PolynomialCoeffs<F>>,
+        rate_bits: usize,
+        blinding: bool,
+        cap_height: usize,
+        timing: &mut TimingTree,
+        fft_root_table: Option<&FftRootTable<F>>,
+        storage: &ColumnStorage,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        ensure!(!polynomials.is_empty(), "No polynomials to commit to");
+        ensure!(chunk_size > 0, "The chunk size must be positive");
+        match storage {
+            ColumnStorage::Memory => Ok(Self::from_coeffs(
+                polynomials,
+                rate_bits,
+                blinding,
+                cap_height,
+                timing,
+                fft_root_table,
+            )),
+            #[cfg(all(feature = "mmap", unix))]
+            ColumnStorage::Mapped(dir) => Self::from_coeffs_mapped(
+                polynomials,
+                rate_bits,
+                blinding,
+                cap_height,
+                timing,
+                fft_root_table,
+                dir,
+                chunk_size,
+            ),
+        }
+    }
+
+    #[cfg(all(feature = "mmap", unix))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_coeffs_mapped(
+        polynomials: Vec<PolynomialCoeffs<F>>,
+        rate_bits: usize,
+        blinding: bool,
+        cap_height: usize,
+        timing: &mut TimingTree,
+        fft_root_table: Option<&FftRootTable<F>>,
+        dir: &std::path::Path,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        let storage = ColumnStorage::Mapped(dir.to_path_buf());
+        let degree = polynomials[0].len();
+        let lde_size = degree << rate_bits;
+        let mut lde_columns = timed!(
+            timing,
+            "FFT",
+            polynomials
+                .par_iter()
+                .map(|p| {
+                    assert_eq!(p.len(), degree, "Polynomial degrees inconsistent");
+                    storage.coset_lde_of_coeffs(&p.coeffs, rate_bits, fft_root_table)
+                })
+                .collect::<std::io::Result<Vec<_>>>()?
+        );
+        // The leaves are in bit-reversed order, so that the transposition reads each column
+        // sequentially.
+        timed!(
+            timing,
+            "bit-reverse LDEs",
+            lde_columns
+                .par_iter_mut()
+                .for_each(|column| reverse_index_bits_in_place(column))
+        );
+
+        // If blinding, salt with random elements to each leaf vector.
+        let salt_size = if blinding { SALT_SIZE } else { 0 };
+        let leaf_len = polynomials.len() + salt_size;
+        let mut leaves = MmapVec::zeroed(lde_size * leaf_len, dir)?;
+        let leaf_hashes = timed!(
+            timing,
+            "transpose and hash LDEs",
+            leaves
+                .par_chunks_mut(chunk_size * leaf_len)
+                .enumerate()
+                .map(|(chunk_index, leaves_chunk)| {
+                    let start = chunk_index * chunk_size;
+                    leaves_chunk
+                        .chunks_exact_mut(leaf_len)
+                        .enumerate()
+                        .map(|(i, leaf)| {
+                            for (value, column) in leaf.iter_mut().zip(&lde_columns) {
+                                *value = column[start + i];
+                            }
+                            for value in &mut leaf[lde_columns.len()..] {
+                                *value = F::rand();
+                            }
+                            C::Hasher::hash_or_noop(leaf)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+                .concat()
+        );
+        drop(lde_columns);
+        let merkle_tree = timed!(
+            timing,
+            "build Merkle tree",
+            MerkleTree::from_leaf_hashes(
+                MerkleLeaves::Mapped {
+                    values: Arc::new(leaves),
+                    leaf_len,
+                },
+                &leaf_hashes,
+                cap_height,
+            )
+        );
+
+        Ok(Self {
+            polynomials,
+            merkle_tree,
+            degree_log: log2_strict(degree),
+            rate_bits,
+            blinding,
+        })
+    }
+
+    /// Like [`Self::from_values`] without blinding, but reuses the coefficients and LDEs of
+    /// `previous` for the polynomials whose values are unchanged, as indicated by `unchanged`. The
+    /// Merkle tree is rebuilt from scratch.
+    pub(crate) fn from_values_reusing(
+        values: Vec<PolynomialValues<F>>,
+        unchanged: &[bool],
+        previous: &Self,
+        cap_height: usize,
+        timing: &mut TimingTree,
+        fft_root_table: Option<&FftRootTable<F>>,
+    ) -> Self {
+        assert!(!previous.blinding, "Cannot reuse a blinded commitment");
+        assert_eq!(values.len(), previous.polynomials.len());
+        assert_eq!(unchanged.len(), previous.polynomials.len());
+        let rate_bits = previous.rate_bits;
+
+        let changed: Vec<usize> = (0..values.len()).filter(|&i| !unchanged[i]).collect();
+        let mut polynomials = previous.polynomials.clone();
+        let changed_coeffs = timed!(
+            timing,
+            "IFFT",
+            changed
+                .par_iter()
+                .map(|&i| values[i].clone().ifft())
+                .collect::<Vec<_>>()
+        );
+        let changed_lde_values = if changed.is_empty() {
+            Vec::new()
+        } else {
+            timed!(
+                timing,
+                "FFT",
+                Self::lde_values(&changed_coeffs, rate_bits, false, fft_root_table)
+            )
+        };
+
+        let leaves = timed!(timing, "patch LDEs", {
+            let mut leaves = previous.merkle_tree.leaves.to_vecs();
+            for (&i, mut lde) in changed.iter().zip(changed_lde_values) {
+                reverse_index_bits_in_place(&mut lde);
+                leaves
+                    .par_iter_mut()
+                    .zip(lde)
+                    .for_each(|(leaf, value)| leaf[i] = value);
+            }
+            leaves
+        });
+        for (&i, coeffs) in changed.iter().zip(changed_coeffs) {
+            polynomials[i] = coeffs;
+        }
+        let merkle_tree = timed!(
+            timing,
+            "build Merkle tree",
+            MerkleTree::new(leaves, cap_height)
+        );
+
+        Self {
+            polynomials,
+            merkle_tree,
+            degree_log: previous.degree_log,
+            rate_bits,
+            blinding: false,
+        }
+    }
+
     pub(crate) fn lde_values(
         polynomials: &[PolynomialCoeffs<F>],
         rate_bits: usize,
@@ -12,6 +12,7 @@ use crate::field::extension::Extendable;
 use crate::field::types::Field64;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::exponentiation::ExponentiationGate;
+use crate::gates::mul_add::MulAddGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::generator::{GeneratedValues, SimpleGenerator};
 use crate::iop::target::{BoolTarget, Target};
@@ -20,6 +21,9 @@ use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
+/// The number of products fused by each operation of [`CircuitBuilder::mul_add_many`].
+pub const NUM_FUSED_MUL_ADD_TERMS: usize = 4;
+
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Computes `-x`.
     pub fn neg(&mut self, x: Target) -> Target {
@@ -166,6 +170,43 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.arithmetic(F::ONE, F::ONE, x, y, z)
     }
 
+    /// Computes `x_0 * y_0 + ... + x_{n-1} * y_{n-1} + z`.
+    ///
+    /// Unlike a chain of [`mul_add`](Self::mul_add) calls, which takes one operation of an
+    /// `ArithmeticGate` per product, this fuses up to [`NUM_FUSED_MUL_ADD_TERMS`] products per
+    /// operation of a [`MulAddGate`], without routing the intermediate sums.
+    pub fn mul_add_many<T>(&mut self, terms: impl IntoIterator<Item = (T, T)>, z: Target) -> Target
+    where
+        T: Borrow<Target>,
+    {
+        let terms = terms
+            .into_iter()
+            .map(|(x, y)| (*x.borrow(), *y.borrow()))
+            .collect::<Vec<_>>();
+        let gate = MulAddGate::new_from_config(&self.config, NUM_FUSED_MUL_ADD_TERMS);
+        if terms.len() < 2 || gate.num_ops == 0 || !self.config.use_base_arithmetic_gate {
+            return terms
+                .into_iter()
+                .fold(z, |acc, (x, y)| self.mul_add(x, y, acc));
+        }
+
+        let zero = self.zero();
+        terms.chunks(NUM_FUSED_MUL_ADD_TERMS).fold(z, |acc, chunk| {
+            let (row, i) = self.find_slot(gate.clone(), &[], &[]);
+            let padding = core::iter::repeat((zero, zero));
+            let factors = chunk
+                .iter()
+                .copied()
+                .chain(padding)
+                .flat_map(|(x, y)| [x, y]);
+            for (wire, factor) in gate.wires_ith_factors(i).zip(factors) {
+                self.connect(factor, Target::wire(row, wire));
+            }
+            self.connect(acc, Target::wire(row, gate.wire_ith_addend(i)));
+            Target::wire(row, gate.wire_ith_output(i))
+        })
+    }
+
     /// Computes `x + C`.
     pub fn add_const(&mut self, x: Target, c: F) -> Target {
         let c = self.constant(c);
@@ -438,3 +479,51 @@ pub(crate) struct BaseArithmeticOperation<F: Field64> {
     multiplicand_1: Target,
     addend: Target,
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_mul_add_many() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let num_terms = 9;
+        let xs = builder.add_virtual_targets(num_terms);
+        let ys = builder.add_virtual_targets(num_terms);
+        let z = builder.add_virtual_target();
+        let result = builder.mul_add_many(xs.iter().zip(&ys), z);
+        builder.register_public_input(result);
+        // The three fused operations fit in a single row, where chained `mul_add`s would take nine
+        // `ArithmeticGate` operations.
+        assert_eq!(builder.num_gates(), 1);
+        let data = builder.build::<C>();
+
+        let x_values = F::rand_vec(num_terms);
+        let y_values = F::rand_vec(num_terms);
+        let z_value = F::rand();
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&xs, &x_values)?;
+        pw.set_target_arr(&ys, &y_values)?;
+        pw.set_target(z, z_value)?;
+        let proof = data.prove(pw)?;
+
+        let expected = x_values
+            .iter()
+            .zip(&y_values)
+            .fold(z_value, |acc, (&x, &y)| acc + x * y);
+        assert_eq!(proof.public_inputs, vec![expected]);
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
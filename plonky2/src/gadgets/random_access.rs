@@ -30,6 +30,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         if vec_size == 1 {
             return v[0];
         }
+        let max_bits = RandomAccessGate::<F, D>::max_bits(&self.config);
+        if bits > max_bits {
+            return self.random_access_multi_level(access_index, v, bits, max_bits);
+        }
         let claimed_element = self.add_virtual_target();
 
         let dummy_gate = RandomAccessGate::<F, D>::new_from_config(&self.config, bits);
@@ -50,6 +54,27 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         claimed_element
     }
 
+    /// Accesses a vector of `2^bits` elements, too large for a single `RandomAccessGate`, by
+    /// splitting the index into its `low_bits` low bits and its remaining high bits. The low bits
+    /// select an element in each chunk of `2^low_bits` consecutive elements, and the high bits
+    /// then select one of these, with further levels if there are still too many chunks.
+    fn random_access_multi_level(
+        &mut self,
+        access_index: Target,
+        v: Vec<Target>,
+        bits: usize,
+        low_bits: usize,
+    ) -> Target {
+        let index_bits = self.split_le(access_index, bits);
+        let low_index = self.le_sum(index_bits[..low_bits].iter());
+        let high_index = self.le_sum(index_bits[low_bits..].iter());
+        let selected = v
+            .chunks(1 << low_bits)
+            .map(|chunk| self.random_access(low_index, chunk.to_vec()))
+            .collect();
+        self.random_access(high_index, selected)
+    }
+
     /// Like `random_access`, but with `ExtensionTarget`s rather than simple `Target`s.
     pub fn random_access_extension(
         &mut self,
@@ -126,7 +151,7 @@ mod tests {
 
     use super::*;
     use crate::field::types::{Field, Sample};
-    use crate::iop::witness::PartialWitness;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
     use crate::plonk::verifier::verify;
@@ -163,4 +188,36 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_random_access_large() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        // More than the 64 elements of a single gate, and not a power of two.
+        let len = 300;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let vec = F::rand_vec(len);
+        let v = builder.add_virtual_targets(len);
+        let indices = [0, 63, 64, 200, 299];
+        let index_targets = builder.add_virtual_targets(indices.len());
+        for &index in &index_targets {
+            let res = builder.random_access(index, v.clone());
+            builder.register_public_input(res);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&v, &vec)?;
+        for (&target, &index) in index_targets.iter().zip(&indices) {
+            pw.set_target(target, F::from_canonical_usize(index))?;
+        }
+        let proof = data.prove(pw)?;
+        assert_eq!(
+            proof.public_inputs,
+            indices.iter().map(|&i| vec[i]).collect::<Vec<_>>()
+        );
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
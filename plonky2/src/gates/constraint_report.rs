@@ -0,0 +1,132 @@
+//! Rendering of the constraints of a [`Gate`] as readable polynomials, for audits.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::Gate;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::EvaluationTargets;
+use crate::util::symbolic::ConstraintReport;
+
+/// Renders the unfiltered constraints of `gate` as polynomials over its wires, its constants and
+/// the public inputs hash, by symbolically evaluating [`Gate::eval_unfiltered_circuit`].
+///
+/// Wires are named after `wire_names`, or `wire[i]` past its end; constants are named `const[i]`
+/// and the elements of the public inputs hash `pi_hash[i]`. Degrees count wires and constants as
+/// degree 1, as in [`Gate::degree`]. Constraints with more than `max_terms` terms are not
+/// rendered.
+pub fn gate_constraint_report<F: RichField + Extendable<D>, G: Gate<F, D>, const D: usize>(
+    gate: &G,
+    wire_names: &[&str],
+    max_terms: usize,
+) -> ConstraintReport {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+
+    let local_wires = builder.add_virtual_extension_targets(gate.num_wires());
+    let local_constants = builder.add_virtual_extension_targets(gate.num_constants());
+    let public_inputs_hash = builder.add_virtual_hash();
+    let vars = EvaluationTargets {
+        local_constants: &local_constants,
+        local_wires: &local_wires,
+        public_inputs_hash: &public_inputs_hash,
+    };
+    let constraints = gate.eval_unfiltered_circuit(&mut builder, vars);
+
+    let public_inputs_hash = public_inputs_hash
+        .elements
+        .map(|t| builder.convert_to_ext(t));
+    let inputs = [local_wires, local_constants, public_inputs_hash.to_vec()].concat();
+    let dag = builder.symbolic_dag(&inputs, &constraints);
+
+    let variables = (0..gate.num_wires())
+        .map(|i| match wire_names.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("wire[{}]", i),
+        })
+        .chain((0..gate.num_constants()).map(|i| format!("const[{}]", i)))
+        .chain((0..public_inputs_hash.len()).map(|i| format!("pi_hash[{}]", i)))
+        .collect();
+    let input_degrees = [
+        vec![1; gate.num_wires() + gate.num_constants()],
+        vec![0; public_inputs_hash.len()],
+    ]
+    .concat();
+
+    ConstraintReport::new(gate.id(), variables, &dag, &input_degrees, max_terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+    use crate::gates::constant::ConstantGate;
+    use crate::gates::poseidon::PoseidonGate;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_constant_gate_report() {
+        let report = gate_constraint_report::<F, _, D>(&ConstantGate::new(2), &["x", "y"], 100);
+        assert_eq!(
+            report.to_string(),
+            "# ConstantGate { num_consts: 2 }\n\
+             constraint 0 (degree 1): -x + const[0] = 0\n\
+             constraint 1 (degree 1): -y + const[1] = 0\n"
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_extension_gate_report() {
+        let gate = ArithmeticExtensionGate::<D> { num_ops: 1 };
+        let report = gate_constraint_report::<F, _, D>(&gate, &[], 100);
+        assert_eq!(
+            report.constraints.len(),
+            Gate::<F, D>::num_constraints(&gate)
+        );
+        for constraint in &report.constraints {
+            assert_eq!(constraint.filter, None);
+            assert_eq!(constraint.degree, Some(3));
+            assert!(constraint.polynomial.is_some());
+        }
+        // The first limb of `output - (const_0 * m_0 * m_1 + const_1 * addend)`, where the
+        // product of the second limbs is multiplied by the extension field's `W = 7`.
+        assert_eq!(
+            report.constraints[0].polynomial.as_deref(),
+            Some(
+                "-wire[0]*wire[2]*const[0] - 7*wire[1]*wire[3]*const[0] \
+                 - wire[4]*const[1] + wire[6]"
+            )
+        );
+    }
+
+    #[test]
+    fn test_max_terms() {
+        // Each constraint has 4 terms, as above.
+        let gate = ArithmeticExtensionGate::<D> { num_ops: 1 };
+        let report = gate_constraint_report::<F, _, D>(&gate, &[], 3);
+        assert!(report.constraints.iter().all(|c| c.polynomial.is_none()));
+        assert!(report.to_string().contains("too many terms"));
+
+        // The recursive Poseidon constraints use the outputs of `PoseidonMdsGate`s, which are
+        // not recovered as arithmetic.
+        let gate = PoseidonGate::<F, D>::new();
+        let report = gate_constraint_report::<F, _, D>(&gate, &[], 1000);
+        assert_eq!(
+            report.constraints.len(),
+            Gate::<F, D>::num_constraints(&gate)
+        );
+        assert!(report.constraints.iter().any(|c| c.degree.is_none()));
+        assert!(report.to_string().contains("opaque["));
+    }
+}
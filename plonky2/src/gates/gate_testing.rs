@@ -2,13 +2,17 @@
 use alloc::{vec, vec::Vec};
 
 use anyhow::{ensure, Result};
+use itertools::Itertools;
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::{Field, Sample};
 use crate::gates::gate::Gate;
 use crate::hash::hash_types::{HashOut, RichField};
-use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::iop::generator::GeneratedValues;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
 use crate::plonk::config::GenericConfig;
@@ -161,3 +165,213 @@ pub fn test_eval_fns<
     let proof = data.prove(pw)?;
     verify::<F, C, D>(proof, &data.verifier_only, &data.common)
 }
+
+/// Tests that the generators of the given gate produce witnesses satisfying its constraints.
+///
+/// The generators are run as in witness generation. Whenever none of them can make progress, one of
+/// the wires they watch is set to a value given by `sample_input`, which defaults to random values in
+/// [`test_gate`](crate::test_gate). Wires which are neither watched nor generated are sampled last.
+/// The constants of the gate are random, and copied to its
+/// [`extra_constant_wires`](Gate::extra_constant_wires).
+pub fn test_generators<F: RichField + Extendable<D>, G: Gate<F, D>, const D: usize>(
+    gate: G,
+    mut sample_input: impl FnMut(usize) -> F,
+) -> Result<()> {
+    let num_wires = gate.num_wires();
+    let constants = F::rand_vec(gate.num_constants());
+    let generators = gate.generators(0, &constants);
+
+    // A witness for a single row, in which each wire is its own partition.
+    let representative_map: Vec<usize> = (0..num_wires).collect();
+    let mut witness = PartitionWitness::new(num_wires, 1, &representative_map);
+    let wire_column = |t: Target| match t {
+        Target::Wire(Wire { row: 0, column }) => Ok(column),
+        _ => Err(anyhow::anyhow!(
+            "Generators may only access the wires of their gate, got {:?}",
+            t
+        )),
+    };
+
+    // The circuit builder sets the wires holding constants of the gate.
+    for (constant_index, column) in gate.extra_constant_wires() {
+        witness.set_target(Target::wire(0, column), constants[constant_index])?;
+    }
+
+    let mut finished = vec![false; generators.len()];
+    let mut buffer = GeneratedValues::empty();
+    while finished.iter().any(|&f| !f) {
+        let mut progress = false;
+        for (generator, finished) in generators.iter().zip(finished.iter_mut()) {
+            if *finished {
+                continue;
+            }
+            *finished = generator.0.run(&witness, &mut buffer);
+            progress |= *finished || !buffer.target_values.is_empty();
+            for (t, v) in buffer.target_values.drain(..) {
+                wire_column(t)?;
+                witness.set_target(t, v)?;
+            }
+        }
+
+        if !progress {
+            // Sample an input of a generator which is still waiting for it.
+            let input = generators
+                .iter()
+                .zip(&finished)
+                .filter(|(_, &finished)| !finished)
+                .flat_map(|(generator, _)| generator.0.watch_list())
+                .find(|&t| witness.try_get_target(t).is_none());
+            let Some(input) = input else {
+                anyhow::bail!("Generators of {} are stuck", gate.id());
+            };
+            witness.set_target(input, sample_input(wire_column(input)?))?;
+        }
+    }
+
+    let wires = (0..num_wires)
+        .map(|column| {
+            let value = witness
+                .try_get_target(Target::wire(0, column))
+                .unwrap_or_else(|| sample_input(column));
+            F::Extension::from_basefield(value)
+        })
+        .collect::<Vec<_>>();
+    let constants = constants
+        .into_iter()
+        .map(F::Extension::from_basefield)
+        .collect::<Vec<_>>();
+    let vars = EvaluationVars {
+        local_constants: &constants,
+        local_wires: &wires,
+        public_inputs_hash: &HashOut::rand(),
+    };
+    let constraints = gate.eval_unfiltered(vars);
+    ensure!(
+        constraints.iter().all(|c| c.is_zero()),
+        "The witness generated for {} violates its constraints {:?}",
+        gate.id(),
+        constraints
+            .iter()
+            .positions(|c| !c.is_zero())
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+/// Generates a module of tests for a gate: [`test_low_degree`], [`test_eval_fns`] and
+/// [`test_generators`], over the Goldilocks field with [`PoseidonGoldilocksConfig`](crate::plonk::config::PoseidonGoldilocksConfig).
+///
+/// ```
+/// # use plonky2::gates::arithmetic_base::ArithmeticGate;
+/// # use plonky2::plonk::circuit_data::CircuitConfig;
+/// plonky2::test_gate!(
+///     arithmetic_gate,
+///     ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config())
+/// );
+/// ```
+///
+/// Gates whose generators expect inputs in a certain range can pass a function sampling the input
+/// of each wire, given its column, e.g. `test_gate!(my_gate, MyGate::new(), sample_input = |_| F::ZERO)`.
+/// Within it, `F` is the field type.
+#[macro_export]
+macro_rules! test_gate {
+    ($name:ident, $gate:expr) => {
+        $crate::test_gate!(
+            $name,
+            $gate,
+            sample_input = |_| <F as $crate::field::types::Sample>::rand()
+        );
+    };
+    ($name:ident, $gate:expr, sample_input = $sample_input:expr) => {
+        mod $name {
+            use $crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+            #[allow(unused_imports)]
+            use super::*;
+
+            const D: usize = 2;
+            type C = PoseidonGoldilocksConfig;
+            #[allow(dead_code)]
+            type F = <C as GenericConfig<D>>::F;
+
+            #[test]
+            fn low_degree() {
+                $crate::gates::gate_testing::test_low_degree::<F, _, D>($gate);
+            }
+
+            #[test]
+            fn eval_fns() {
+                $crate::gates::gate_testing::test_eval_fns::<F, C, _, D>($gate).unwrap();
+            }
+
+            #[test]
+            fn generators() {
+                $crate::gates::gate_testing::test_generators::<F, _, D>($gate, $sample_input)
+                    .unwrap();
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::types::{Field, PrimeField64, Sample};
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+    use crate::gates::base_sum::BaseSumGate;
+    use crate::gates::constant::ConstantGate;
+    use crate::gates::coset_interpolation::CosetInterpolationGate;
+    use crate::gates::exponentiation::ExponentiationGate;
+    use crate::gates::mul_add::MulAddGate;
+    use crate::gates::multiplication_extension::MulExtensionGate;
+    use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::random_access::RandomAccessGate;
+    use crate::gates::reducing::ReducingGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    fn config() -> CircuitConfig {
+        CircuitConfig::standard_recursion_config()
+    }
+
+    fn random_bit<F: Field + PrimeField64 + Sample>() -> F {
+        F::from_bool(F::rand().to_canonical_u64() & 1 == 1)
+    }
+
+    crate::test_gate!(arithmetic, ArithmeticGate::new_from_config(&config()));
+    crate::test_gate!(
+        arithmetic_extension,
+        ArithmeticExtensionGate::new_from_config(&config())
+    );
+    crate::test_gate!(mul_add, MulAddGate::new_from_config(&config(), 4));
+    crate::test_gate!(mul_extension, MulExtensionGate::new_from_config(&config()));
+    crate::test_gate!(constant, ConstantGate::new(2));
+    crate::test_gate!(reducing, ReducingGate::new(10));
+    crate::test_gate!(coset_interpolation, CosetInterpolationGate::new(2));
+    // The generators of these gates expect inputs in a certain range.
+    crate::test_gate!(
+        poseidon,
+        PoseidonGate::<F, D>::new(),
+        sample_input = |column| match column {
+            PoseidonGate::<F, D>::WIRE_SWAP => random_bit(),
+            _ => F::rand(),
+        }
+    );
+    crate::test_gate!(
+        exponentiation,
+        ExponentiationGate::new_from_config(&config()),
+        sample_input = |column| match column {
+            0 => F::rand(),
+            _ => random_bit(),
+        }
+    );
+    crate::test_gate!(
+        base_sum,
+        BaseSumGate::<2>::new(10),
+        sample_input = |_| F::from_canonical_u64(F::rand().to_canonical_u64() % 1024)
+    );
+    crate::test_gate!(
+        random_access,
+        RandomAccessGate::new_from_config(&config(), 2),
+        sample_input = |_| F::from_canonical_u64(F::rand().to_canonical_u64() % 4)
+    );
+}
@@ -27,11 +27,13 @@ pub mod arithmetic_base;
 pub mod arithmetic_extension;
 pub mod base_sum;
 pub mod constant;
+pub mod constraint_report;
 pub mod coset_interpolation;
 pub mod exponentiation;
 pub mod gate;
 pub mod lookup;
 pub mod lookup_table;
+pub mod mul_add;
 pub mod multiplication_extension;
 pub mod noop;
 pub mod packed_util;
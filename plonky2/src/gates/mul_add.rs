@@ -0,0 +1,274 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::Range;
+
+use anyhow::Result;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which can perform a fused multiply-add of several products, i.e.
+/// `result = x_0.y_0 + ... + x_{k-1}.y_{k-1} + z` with `k = num_terms`. Compared to chaining `k`
+/// operations of an [`ArithmeticGate`](crate::gates::arithmetic_base::ArithmeticGate), this saves
+/// routing the `k - 1` intermediate sums. If the config has enough routed wires, it can support
+/// several such operations in one gate.
+#[derive(Debug, Clone, Default)]
+pub struct MulAddGate {
+    /// Number of products summed by each operation.
+    pub num_terms: usize,
+    /// Number of operations performed by the gate.
+    pub num_ops: usize,
+}
+
+impl MulAddGate {
+    pub const fn new_from_config(config: &CircuitConfig, num_terms: usize) -> Self {
+        Self {
+            num_terms,
+            num_ops: Self::num_ops(config, num_terms),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) const fn num_ops(config: &CircuitConfig, num_terms: usize) -> usize {
+        config.num_routed_wires / Self::wires_per_op(num_terms)
+    }
+
+    const fn wires_per_op(num_terms: usize) -> usize {
+        2 * num_terms + 2
+    }
+
+    /// The wires of the factors of the `i`-th operation, alternating `x_j` and `y_j`.
+    pub(crate) const fn wires_ith_factors(&self, i: usize) -> Range<usize> {
+        let start = Self::wires_per_op(self.num_terms) * i;
+        start..start + 2 * self.num_terms
+    }
+    pub(crate) const fn wire_ith_addend(&self, i: usize) -> usize {
+        Self::wires_per_op(self.num_terms) * i + 2 * self.num_terms
+    }
+    pub(crate) const fn wire_ith_output(&self, i: usize) -> usize {
+        Self::wires_per_op(self.num_terms) * i + 2 * self.num_terms + 1
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulAddGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_terms)?;
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_terms = src.read_usize()?;
+        let num_ops = src.read_usize()?;
+        Ok(Self { num_terms, num_ops })
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops);
+        for i in 0..self.num_ops {
+            let factors = &vars.local_wires[self.wires_ith_factors(i)];
+            let addend = vars.local_wires[self.wire_ith_addend(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            let computed_output = factors
+                .chunks_exact(2)
+                .fold(addend, |acc, xy| acc + xy[0] * xy[1]);
+
+            constraints.push(output - computed_output);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops);
+        for i in 0..self.num_ops {
+            let factors = &vars.local_wires[self.wires_ith_factors(i)];
+            let addend = vars.local_wires[self.wire_ith_addend(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            let computed_output = factors.chunks_exact(2).fold(addend, |acc, xy| {
+                builder.mul_add_extension(xy[0], xy[1], acc)
+            });
+
+            let diff = builder.sub_extension(output, computed_output);
+            constraints.push(diff);
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    MulAddGenerator {
+                        row,
+                        gate: self.clone(),
+                        i,
+                    }
+                    .adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::wires_per_op(self.num_terms)
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for MulAddGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let addend = vars.local_wires[self.wire_ith_addend(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            let computed_output = self.wires_ith_factors(i).step_by(2).fold(addend, |acc, j| {
+                acc + vars.local_wires[j] * vars.local_wires[j + 1]
+            });
+
+            yield_constr.one(output - computed_output);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MulAddGenerator {
+    row: usize,
+    gate: MulAddGate,
+    i: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MulAddGenerator {
+    fn id(&self) -> String {
+        "MulAddGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.gate
+            .wires_ith_factors(self.i)
+            .chain([self.gate.wire_ith_addend(self.i)])
+            .map(|i| Target::wire(self.row, i))
+            .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let factors = self
+            .gate
+            .wires_ith_factors(self.i)
+            .map(get_wire)
+            .collect::<Vec<_>>();
+        let addend = get_wire(self.gate.wire_ith_addend(self.i));
+
+        let output_target = Target::wire(self.row, self.gate.wire_ith_output(self.i));
+
+        let computed_output = factors
+            .chunks_exact(2)
+            .fold(addend, |acc, xy| acc + xy[0] * xy[1]);
+
+        out_buffer.set_target(output_target, computed_output)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.gate.num_terms)?;
+        dst.write_usize(self.gate.num_ops)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let num_terms = src.read_usize()?;
+        let num_ops = src.read_usize()?;
+        let i = src.read_usize()?;
+        Ok(Self {
+            row,
+            gate: MulAddGate { num_terms, num_ops },
+            i,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::mul_add::MulAddGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = MulAddGate::new_from_config(&CircuitConfig::standard_recursion_config(), 4);
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = MulAddGate::new_from_config(&CircuitConfig::standard_recursion_config(), 4);
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
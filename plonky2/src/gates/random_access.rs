@@ -75,6 +75,18 @@ impl<F: RichField + Extendable<D>, const D: usize> RandomAccessGate<F, D> {
         )
     }
 
+    /// The largest number of index bits for which a gate with at least one copy fits in the given
+    /// config.
+    pub fn max_bits(config: &CircuitConfig) -> usize {
+        (0..)
+            .take_while(|&bits| {
+                let vec_size = 1 << bits;
+                2 + vec_size <= config.num_routed_wires && 2 + vec_size + bits <= config.num_wires
+            })
+            .last()
+            .expect("The config doesn't have enough wires for random access")
+    }
+
     /// Length of the list being accessed.
     const fn vec_size(&self) -> usize {
         1 << self.bits
@@ -1,7 +1,10 @@
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::mem::MaybeUninit;
+use core::ops::Index;
 use core::slice;
+#[cfg(all(feature = "mmap", unix))]
+use std::sync::Arc;
 
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
@@ -10,6 +13,8 @@ use crate::hash::hash_types::RichField;
 use crate::hash::merkle_proofs::MerkleProof;
 use crate::plonk::config::{GenericHashOut, Hasher};
 use crate::util::log2_strict;
+#[cfg(all(feature = "mmap", unix))]
+use crate::util::mmap::MmapVec;
 
 /// The Merkle cap of height `h` of a Merkle tree is the `h`-th layer (from the root) of the tree.
 /// It can be used in place of the root to verify Merkle paths, which are `h` elements shorter.
@@ -42,10 +47,87 @@ impl<F: RichField, H: Hasher<F>> MerkleCap<F, H> {
     }
 }
 
+/// The leaves of a [`MerkleTree`].
+#[derive(Clone, Debug)]
+pub enum MerkleLeaves<F: RichField> {
+    /// Leaves in memory.
+    Memory(Vec<Vec<F>>),
+    /// Leaves of `leaf_len` elements each, stored one after the other in a memory-mapped file.
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped {
+        /// The concatenated leaves.
+        values: Arc<MmapVec<F>>,
+        /// The length of each leaf.
+        leaf_len: usize,
+    },
+}
+
+impl<F: RichField> MerkleLeaves<F> {
+    /// The number of leaves.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Memory(leaves) => leaves.len(),
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped { values, leaf_len } => values.len() / leaf_len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the leaves.
+    pub fn iter(&self) -> impl Iterator<Item = &[F]> {
+        (0..self.len()).map(|i| &self[i])
+    }
+
+    /// Copies the leaves to memory.
+    pub fn to_vecs(&self) -> Vec<Vec<F>> {
+        match self {
+            Self::Memory(leaves) => leaves.clone(),
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped { .. } => self.iter().map(<[F]>::to_vec).collect(),
+        }
+    }
+}
+
+impl<F: RichField> Default for MerkleLeaves<F> {
+    fn default() -> Self {
+        Self::Memory(Vec::new())
+    }
+}
+
+impl<F: RichField> From<Vec<Vec<F>>> for MerkleLeaves<F> {
+    fn from(leaves: Vec<Vec<F>>) -> Self {
+        Self::Memory(leaves)
+    }
+}
+
+impl<F: RichField> Index<usize> for MerkleLeaves<F> {
+    type Output = [F];
+
+    fn index(&self, i: usize) -> &[F] {
+        match self {
+            Self::Memory(leaves) => &leaves[i],
+            #[cfg(all(feature = "mmap", unix))]
+            Self::Mapped { values, leaf_len } => &values[i * leaf_len..(i + 1) * leaf_len],
+        }
+    }
+}
+
+/// Leaves are compared by value, wherever they are stored.
+impl<F: RichField> PartialEq for MerkleLeaves<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<F: RichField> Eq for MerkleLeaves<F> {}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MerkleTree<F: RichField, H: Hasher<F>> {
     /// The data in the leaves of the Merkle tree.
-    pub leaves: Vec<Vec<F>>,
+    pub leaves: MerkleLeaves<F>,
 
     /// The digests in the tree. Consists of `cap.len()` sub-trees, each corresponding to one
     /// element in `cap`. Each subtree is contiguous and located at
@@ -64,7 +146,7 @@ pub struct MerkleTree<F: RichField, H: Hasher<F>> {
 impl<F: RichField, H: Hasher<F>> Default for MerkleTree<F, H> {
     fn default() -> Self {
         Self {
-            leaves: Vec::new(),
+            leaves: MerkleLeaves::default(),
             digests: Vec::new(),
             cap: MerkleCap::default(),
         }
@@ -83,13 +165,16 @@ pub(crate) fn capacity_up_to_mut<T>(v: &mut Vec<T>, len: usize) -> &mut [MaybeUn
     }
 }
 
-pub(crate) fn fill_subtree<F: RichField, H: Hasher<F>>(
+/// Fills the digests of the subtree over `leaves`, each leaf being hashed with `hash_leaf`, and
+/// returns its root.
+fn fill_subtree<F: RichField, H: Hasher<F>, L: Sync>(
     digests_buf: &mut [MaybeUninit<H::Hash>],
-    leaves: &[Vec<F>],
+    leaves: &[L],
+    hash_leaf: &(impl Fn(&L) -> H::Hash + Sync),
 ) -> H::Hash {
     assert_eq!(leaves.len(), digests_buf.len() / 2 + 1);
     if digests_buf.is_empty() {
-        H::hash_or_noop(&leaves[0])
+        hash_leaf(&leaves[0])
     } else {
         // Layout is: left recursive output || left child digest
         //             || right child digest || right recursive output.
@@ -102,8 +187,8 @@ pub(crate) fn fill_subtree<F: RichField, H: Hasher<F>>(
         let (left_leaves, right_leaves) = leaves.split_at(leaves.len() / 2);
 
         let (left_digest, right_digest) = plonky2_maybe_rayon::join(
-            || fill_subtree::<F, H>(left_digests_buf, left_leaves),
-            || fill_subtree::<F, H>(right_digests_buf, right_leaves),
+            || fill_subtree::<F, H, L>(left_digests_buf, left_leaves, hash_leaf),
+            || fill_subtree::<F, H, L>(right_digests_buf, right_leaves, hash_leaf),
         );
 
         left_digest_mem.write(left_digest);
@@ -117,6 +202,19 @@ pub(crate) fn fill_digests_buf<F: RichField, H: Hasher<F>>(
     cap_buf: &mut [MaybeUninit<H::Hash>],
     leaves: &[Vec<F>],
     cap_height: usize,
+) {
+    fill_digests_buf_with::<F, H, _>(digests_buf, cap_buf, leaves, cap_height, &|leaf| {
+        H::hash_or_noop(leaf)
+    });
+}
+
+/// Like [`fill_digests_buf`], but for any kind of leaves, hashed with `hash_leaf`.
+fn fill_digests_buf_with<F: RichField, H: Hasher<F>, L: Sync>(
+    digests_buf: &mut [MaybeUninit<H::Hash>],
+    cap_buf: &mut [MaybeUninit<H::Hash>],
+    leaves: &[L],
+    cap_height: usize,
+    hash_leaf: &(impl Fn(&L) -> H::Hash + Sync),
 ) {
     // Special case of a tree that's all cap. The usual case will panic because we'll try to split
     // an empty slice into chunks of `0`. (We would not need this if there was a way to split into
@@ -127,7 +225,7 @@ pub(crate) fn fill_digests_buf<F: RichField, H: Hasher<F>>(
             .par_iter_mut()
             .zip(leaves)
             .for_each(|(cap_buf, leaf)| {
-                cap_buf.write(H::hash_or_noop(leaf));
+                cap_buf.write(hash_leaf(leaf));
             });
         return;
     }
@@ -143,7 +241,11 @@ pub(crate) fn fill_digests_buf<F: RichField, H: Hasher<F>>(
             // We have `1 << cap_height` sub-trees, one for each entry in `cap`. They are totally
             // independent, so we schedule one task for each. `digests_buf` and `leaves` are split
             // into `1 << cap_height` slices, one for each sub-tree.
-            subtree_cap.write(fill_subtree::<F, H>(subtree_digests, subtree_leaves));
+            subtree_cap.write(fill_subtree::<F, H, L>(
+                subtree_digests,
+                subtree_leaves,
+                hash_leaf,
+            ));
         },
     );
 }
@@ -191,6 +293,36 @@ pub(crate) fn merkle_tree_prove<F: RichField, H: Hasher<F>>(
 
 impl<F: RichField, H: Hasher<F>> MerkleTree<F, H> {
     pub fn new(leaves: Vec<Vec<F>>, cap_height: usize) -> Self {
+        let (digests, cap) =
+            Self::digests_and_cap(&leaves, cap_height, &|leaf: &Vec<F>| H::hash_or_noop(leaf));
+        Self {
+            leaves: leaves.into(),
+            digests,
+            cap,
+        }
+    }
+
+    /// Creates a tree over `leaves`, given the hash of each leaf, e.g. computed while writing the
+    /// leaves out of memory.
+    pub fn from_leaf_hashes(
+        leaves: MerkleLeaves<F>,
+        leaf_hashes: &[H::Hash],
+        cap_height: usize,
+    ) -> Self {
+        assert_eq!(leaves.len(), leaf_hashes.len());
+        let (digests, cap) = Self::digests_and_cap(leaf_hashes, cap_height, &|&hash| hash);
+        Self {
+            leaves,
+            digests,
+            cap,
+        }
+    }
+
+    fn digests_and_cap<L: Sync>(
+        leaves: &[L],
+        cap_height: usize,
+        hash_leaf: &(impl Fn(&L) -> H::Hash + Sync),
+    ) -> (Vec<H::Hash>, MerkleCap<F, H>) {
         let log2_leaves_len = log2_strict(leaves.len());
         assert!(
             cap_height <= log2_leaves_len,
@@ -207,20 +339,16 @@ impl<F: RichField, H: Hasher<F>> MerkleTree<F, H> {
 
         let digests_buf = capacity_up_to_mut(&mut digests, num_digests);
         let cap_buf = capacity_up_to_mut(&mut cap, len_cap);
-        fill_digests_buf::<F, H>(digests_buf, cap_buf, &leaves[..], cap_height);
+        fill_digests_buf_with::<F, H, L>(digests_buf, cap_buf, leaves, cap_height, hash_leaf);
 
         unsafe {
-            // SAFETY: `fill_digests_buf` and `cap` initialized the spare capacity up to
+            // SAFETY: `fill_digests_buf_with` and `cap` initialized the spare capacity up to
             // `num_digests` and `len_cap`, resp.
             digests.set_len(num_digests);
             cap.set_len(len_cap);
         }
 
-        Self {
-            leaves,
-            digests,
-            cap: MerkleCap(cap),
-        }
+        (digests, MerkleCap(cap))
     }
 
     pub fn get(&self, i: usize) -> &[F] {
@@ -309,4 +437,23 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_from_leaf_hashes() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let leaves = random_data::<F>(1 << 6, 7);
+        let tree = MerkleTree::<F, H>::new(leaves.clone(), 2);
+        let leaf_hashes = leaves
+            .iter()
+            .map(|leaf| H::hash_or_noop(leaf))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            MerkleTree::<F, H>::from_leaf_hashes(leaves.into(), &leaf_hashes, 2),
+            tree
+        );
+    }
 }
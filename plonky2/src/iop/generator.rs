@@ -8,7 +8,7 @@ use alloc::{
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
@@ -33,12 +33,168 @@ pub fn generate_partial_witness<
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
 ) -> Result<PartitionWitness<'a, F>> {
-    let config = &common_data.config;
+    let mut witness = witness_from_inputs(inputs, prover_data, common_data)?;
+
+    // Initially, all generators are queued.
+    let pending_generator_indices: Vec<_> = (0..prover_data.generators.len()).collect();
+    let mut generator_is_expired = vec![false; prover_data.generators.len()];
+    run_generators(
+        &mut witness,
+        pending_generator_indices,
+        &mut generator_is_expired,
+        None,
+        prover_data,
+    )?;
+
+    Ok(witness)
+}
+
+/// A complete witness, along with the representatives of the partitions that each generator
+/// populated while generating it. This is what
+/// [`regenerate_partial_witness`] needs to only re-run the generators affected by a change of
+/// inputs.
+#[derive(Clone, Debug)]
+pub(crate) struct GeneratedWitness<F: Field> {
+    pub(crate) values: Vec<Option<F>>,
+    pub(crate) generator_outputs: Vec<Vec<usize>>,
+}
+
+/// Like [`generate_partial_witness`], but also records the partitions populated by each
+/// generator.
+pub(crate) fn generate_partial_witness_recording_outputs<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<(PartitionWitness<'a, F>, Vec<Vec<usize>>)> {
+    let mut witness = witness_from_inputs(inputs, prover_data, common_data)?;
+
+    let pending_generator_indices: Vec<_> = (0..prover_data.generators.len()).collect();
+    let mut generator_is_expired = vec![false; prover_data.generators.len()];
+    let mut generator_outputs = vec![Vec::new(); prover_data.generators.len()];
+    run_generators(
+        &mut witness,
+        pending_generator_indices,
+        &mut generator_is_expired,
+        Some(&mut generator_outputs),
+        prover_data,
+    )?;
+
+    Ok((witness, generator_outputs))
+}
+
+/// Updates a witness previously generated by [`generate_partial_witness_recording_outputs`] after
+/// the input targets in `delta` changed, re-running only the generators which depend on them.
+///
+/// The targets of `delta` are expected to be inputs of the circuit, i.e. not populated by any
+/// generator. Other generators without a watch list are never re-run, so their previous outputs are
+/// reused, except for the [`RandomValueGenerator`]s of zero-knowledge circuits: these are always
+/// re-run, along with the generators depending on them, so that proofs don't share any blinding
+/// value.
+pub(crate) fn regenerate_partial_witness<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    previous: &GeneratedWitness<F>,
+    delta: &PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<(PartitionWitness<'a, F>, Vec<Vec<usize>>)> {
     let generators = &prover_data.generators;
     let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
 
     let mut witness = PartitionWitness::new(
-        config.num_wires,
+        common_data.config.num_wires,
+        common_data.degree(),
+        &prover_data.representative_map,
+    );
+    ensure!(
+        previous.values.len() == witness.values.len()
+            && previous.generator_outputs.len() == generators.len(),
+        "The previous witness was generated for another circuit"
+    );
+    witness.values.clone_from(&previous.values);
+
+    // Overwrite the changed inputs.
+    let mut changed_reps = Vec::with_capacity(delta.target_values.len());
+    for (&t, &v) in &delta.target_values {
+        let rep = witness.representative_map[witness.target_index(t)];
+        witness.values[rep] = Some(v);
+        changed_reps.push(rep);
+    }
+
+    // Find the generators which transitively depend on the changed inputs, and on fresh blinding
+    // values if any, and clear the partitions they populated.
+    let mut generator_is_affected = vec![false; generators.len()];
+    let mut affected_generator_indices = Vec::new();
+    let mut stack = changed_reps;
+    let mut newly_affected = if common_data.config.zero_knowledge {
+        (0..generators.len())
+            .filter(|&i| generators[i].is_random_value())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    loop {
+        for generator_idx in newly_affected.drain(..) {
+            if generator_is_affected[generator_idx] {
+                continue;
+            }
+            generator_is_affected[generator_idx] = true;
+            affected_generator_indices.push(generator_idx);
+            for &output in &previous.generator_outputs[generator_idx] {
+                if witness.values[output].take().is_some() {
+                    stack.push(output);
+                }
+            }
+        }
+        let Some(rep) = stack.pop() else {
+            break;
+        };
+        newly_affected.extend(
+            generator_indices_by_watches
+                .get(&rep)
+                .into_iter()
+                .flatten()
+                .copied(),
+        );
+    }
+
+    // Re-run the affected generators only, treating the others as expired.
+    let mut generator_outputs = previous.generator_outputs.clone();
+    for &generator_idx in &affected_generator_indices {
+        generator_outputs[generator_idx].clear();
+    }
+    let mut generator_is_expired: Vec<_> = generator_is_affected.iter().map(|&a| !a).collect();
+    run_generators(
+        &mut witness,
+        affected_generator_indices,
+        &mut generator_is_expired,
+        Some(&mut generator_outputs),
+        prover_data,
+    )?;
+
+    Ok((witness, generator_outputs))
+}
+
+fn witness_from_inputs<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<PartitionWitness<'a, F>> {
+    let mut witness = PartitionWitness::new(
+        common_data.config.num_wires,
         common_data.degree(),
         &prover_data.representative_map,
     );
@@ -47,13 +203,24 @@ pub fn generate_partial_witness<
         witness.set_target(t, v)?;
     }
 
-    // Build a list of "pending" generators which are queued to be run. Initially, all generators
-    // are queued.
-    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+    Ok(witness)
+}
+
+/// Runs the pending generators, and the ones watching the targets they populate, until no more
+/// progress can be made. Fails if some generator isn't expired in the end. If `generator_outputs`
+/// is given, the representatives populated by each generator are appended to it.
+fn run_generators<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    witness: &mut PartitionWitness<F>,
+    mut pending_generator_indices: Vec<usize>,
+    generator_is_expired: &mut [bool],
+    mut generator_outputs: Option<&mut Vec<Vec<usize>>>,
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+) -> Result<()> {
+    let generators = &prover_data.generators;
+    let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
 
-    // We also track a list of "expired" generators which have already returned false.
-    let mut generator_is_expired = vec![false; generators.len()];
-    let mut remaining_generators = generators.len();
+    // Expired generators have already returned true, and are never run again.
+    let mut remaining_generators = generator_is_expired.iter().filter(|&&e| !e).count();
 
     let mut buffer = GeneratedValues::empty();
 
@@ -66,7 +233,7 @@ pub fn generate_partial_witness<
                 continue;
             }
 
-            let finished = generators[generator_idx].0.run(&witness, &mut buffer);
+            let finished = generators[generator_idx].0.run(witness, &mut buffer);
             if finished {
                 generator_is_expired[generator_idx] = true;
                 remaining_generators -= 1;
@@ -79,6 +246,9 @@ pub fn generate_partial_witness<
                 let reps = witness.set_target_returning_rep(t, v)?;
                 new_target_reps.extend(reps);
             }
+            if let Some(outputs) = generator_outputs.as_deref_mut() {
+                outputs[generator_idx].extend_from_slice(&new_target_reps);
+            }
 
             // Enqueue unfinished generators that were watching one of the newly populated targets.
             for watch in new_target_reps {
@@ -100,7 +270,7 @@ pub fn generate_partial_witness<
         return Err(anyhow!("{} generators weren't run", remaining_generators));
     }
 
-    Ok(witness)
+    Ok(())
 }
 
 /// A generator participates in the generation of the witness.
@@ -135,6 +305,12 @@ impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorRef<F, D> {
     pub fn new<G: WitnessGenerator<F, D>>(generator: G) -> WitnessGeneratorRef<F, D> {
         WitnessGeneratorRef(Box::new(generator))
     }
+
+    /// Whether this is a [`RandomValueGenerator`], whose output is not determined by the inputs
+    /// of the circuit.
+    pub fn is_random_value(&self) -> bool {
+        self.0.id() == RandomValueGenerator::ID
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> PartialEq for WitnessGeneratorRef<F, D> {
@@ -317,9 +493,14 @@ pub struct RandomValueGenerator {
     pub(crate) target: Target,
 }
 
+impl RandomValueGenerator {
+    /// The [`id`](SimpleGenerator::id) of this generator.
+    pub const ID: &'static str = "RandomValueGenerator";
+}
+
 impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for RandomValueGenerator {
     fn id(&self) -> String {
-        "RandomValueGenerator".to_string()
+        Self::ID.to_string()
     }
 
     fn dependencies(&self) -> Vec<Target> {
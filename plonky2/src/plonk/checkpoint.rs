@@ -0,0 +1,390 @@
+//! Checkpoints of the prover, allowing long proving jobs to resume after a crash or a preemption.
+//!
+//! [`prove_with_checkpoints`](crate::plonk::prover::prove_with_checkpoints) saves the result of
+//! each expensive phase of the prover to a [`CheckpointStore`], and skips the phases whose result
+//! is already in the store. Each checkpoint records what its phase was computed from: the digest
+//! of the circuit, and either a hash of the inputs of the proof for the wires commitment, or the
+//! caps of the commitments of the preceding phases. Loading a checkpoint made for another circuit,
+//! other inputs or other preceding commitments fails.
+//!
+//! The challenger is not saved, as it can be replayed from the commitments. The FRI proof is
+//! computed from scratch when resuming.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::fri::oracle::PolynomialBatch;
+use crate::hash::hash_types::RichField;
+use crate::hash::merkle_tree::MerkleCap;
+use crate::iop::target::Target;
+use crate::iop::witness::PartialWitness;
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::util::serialization::{Buffer, Read, Write};
+
+/// A phase of the prover whose result can be checkpointed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ProverPhase {
+    /// Witness generation and the commitment to the wire polynomials.
+    WiresCommitment,
+    /// The commitment to the partial products, the `Z` polynomials and the lookup polynomials.
+    ZsPartialProductsCommitment,
+    /// The commitment to the quotient polynomials.
+    QuotientCommitment,
+}
+
+impl ProverPhase {
+    /// All the phases, in the order the prover goes through them.
+    pub const ALL: [Self; 3] = [
+        Self::WiresCommitment,
+        Self::ZsPartialProductsCommitment,
+        Self::QuotientCommitment,
+    ];
+
+    /// A short name for this phase, e.g. to be used as a file name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::WiresCommitment => "wires_commitment",
+            Self::ZsPartialProductsCommitment => "zs_partial_products_commitment",
+            Self::QuotientCommitment => "quotient_commitment",
+        }
+    }
+}
+
+/// Storage for the checkpoints of the prover.
+pub trait CheckpointStore {
+    /// Loads the checkpoint of the given phase, if any.
+    fn load(&mut self, phase: ProverPhase) -> Result<Option<Vec<u8>>>;
+
+    /// Saves the checkpoint of the given phase.
+    fn save(&mut self, phase: ProverPhase, checkpoint: &[u8]) -> Result<()>;
+}
+
+/// A [`CheckpointStore`] keeping one file per phase in a directory.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct DirectoryCheckpointStore {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl DirectoryCheckpointStore {
+    /// Creates a store in the given directory, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, phase: ProverPhase) -> PathBuf {
+        self.dir.join(phase.name()).with_extension("bin")
+    }
+
+    /// Removes all the checkpoints of this store, typically once the proof is complete.
+    pub fn clear(&self) -> Result<()> {
+        for phase in ProverPhase::ALL {
+            match std::fs::remove_file(self.path(phase)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl CheckpointStore for DirectoryCheckpointStore {
+    fn load(&mut self, phase: ProverPhase) -> Result<Option<Vec<u8>>> {
+        match std::fs::read(self.path(phase)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&mut self, phase: ProverPhase, checkpoint: &[u8]) -> Result<()> {
+        // Write to a temporary file first, so that a crash never leaves a truncated checkpoint.
+        let path = self.path(phase);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, checkpoint)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Hashes the inputs of a proof, independently of the order they were set in.
+pub(crate) fn hash_inputs<F: RichField, H: Hasher<F>>(inputs: &PartialWitness<F>) -> H::Hash {
+    let mut entries = inputs
+        .target_values
+        .iter()
+        .map(|(&target, &value)| {
+            let key = match target {
+                Target::Wire(wire) => (0, wire.row, wire.column),
+                Target::VirtualTarget { index } => (1, index, 0),
+            };
+            (key, value)
+        })
+        .collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|&(key, _)| key);
+    let elements = entries
+        .into_iter()
+        .flat_map(|((kind, a, b), value)| {
+            [kind, a, b]
+                .map(F::from_canonical_usize)
+                .into_iter()
+                .chain([value])
+        })
+        .collect::<Vec<_>>();
+    H::hash_no_pad(&elements)
+}
+
+/// The result of a phase of the prover.
+pub(crate) struct Checkpoint<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// The hash of the inputs of the proof, only set for [`ProverPhase::WiresCommitment`].
+    pub(crate) inputs_hash: Option<<C::Hasher as Hasher<F>>::Hash>,
+    /// The caps of the commitments of the preceding phases.
+    pub(crate) preceding_caps: Vec<MerkleCap<F, C::Hasher>>,
+    /// The public inputs of the proof, only set for [`ProverPhase::WiresCommitment`].
+    pub(crate) public_inputs: Vec<F>,
+    /// The commitment computed in this phase.
+    pub(crate) commitment: PolynomialBatch<F, C, D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Checkpoint<F, C, D> {
+    pub(crate) fn to_bytes(&self, circuit_digest: &<C::Hasher as Hasher<F>>::Hash) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_hash::<F, C::Hasher>(*circuit_digest)
+            .and_then(|()| buffer.write_bool(self.inputs_hash.is_some()))
+            .and_then(|()| match self.inputs_hash {
+                Some(inputs_hash) => buffer.write_hash::<F, C::Hasher>(inputs_hash),
+                None => Ok(()),
+            })
+            .and_then(|()| buffer.write_usize(self.preceding_caps.len()))
+            .and_then(|()| {
+                self.preceding_caps.iter().try_for_each(|cap| {
+                    buffer.write_usize(cap.height())?;
+                    buffer.write_merkle_cap(cap)
+                })
+            })
+            .and_then(|()| buffer.write_usize(self.public_inputs.len()))
+            .and_then(|()| buffer.write_field_vec(&self.public_inputs))
+            .and_then(|()| buffer.write_polynomial_batch(&self.commitment))
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a checkpoint, checking that it was made for the given circuit, inputs hash and
+    /// preceding caps.
+    pub(crate) fn from_bytes(
+        bytes: &[u8],
+        circuit_digest: &<C::Hasher as Hasher<F>>::Hash,
+        inputs_hash: Option<&<C::Hasher as Hasher<F>>::Hash>,
+        preceding_caps: &[&MerkleCap<F, C::Hasher>],
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let digest = buffer
+            .read_hash::<F, C::Hasher>()
+            .map_err(anyhow::Error::msg)?;
+        ensure!(
+            digest == *circuit_digest,
+            "The checkpoint was made for another circuit"
+        );
+        let saved_inputs_hash = if buffer.read_bool().map_err(anyhow::Error::msg)? {
+            Some(
+                buffer
+                    .read_hash::<F, C::Hasher>()
+                    .map_err(anyhow::Error::msg)?,
+            )
+        } else {
+            None
+        };
+        ensure!(
+            saved_inputs_hash.as_ref() == inputs_hash,
+            "The checkpoint was made for other inputs"
+        );
+        let num_preceding_caps = buffer.read_usize().map_err(anyhow::Error::msg)?;
+        let saved_preceding_caps = (0..num_preceding_caps)
+            .map(|_| {
+                let cap_height = buffer.read_usize()?;
+                buffer.read_merkle_cap(cap_height)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::msg)?;
+        ensure!(
+            saved_preceding_caps
+                .iter()
+                .eq(preceding_caps.iter().copied()),
+            "The checkpoint was made for other preceding commitments"
+        );
+        let num_public_inputs = buffer.read_usize().map_err(anyhow::Error::msg)?;
+        let public_inputs = buffer
+            .read_field_vec(num_public_inputs)
+            .map_err(anyhow::Error::msg)?;
+        let commitment = buffer.read_polynomial_batch().map_err(anyhow::Error::msg)?;
+        ensure!(
+            buffer.unread_bytes().is_empty(),
+            "Trailing bytes in checkpoint"
+        );
+        Ok(Self {
+            inputs_hash: saved_inputs_hash,
+            preceding_caps: saved_preceding_caps,
+            public_inputs,
+            commitment,
+        })
+    }
+
+    /// Loads the checkpoint of the given phase from `store`, if any. Fails if it was made for
+    /// another circuit, other inputs or other preceding commitments.
+    pub(crate) fn load(
+        store: &mut dyn CheckpointStore,
+        phase: ProverPhase,
+        circuit_digest: &<C::Hasher as Hasher<F>>::Hash,
+        inputs_hash: Option<&<C::Hasher as Hasher<F>>::Hash>,
+        preceding_caps: &[&MerkleCap<F, C::Hasher>],
+    ) -> Result<Option<Self>> {
+        store
+            .load(phase)?
+            .map(|bytes| Self::from_bytes(&bytes, circuit_digest, inputs_hash, preceding_caps))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashMap;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::plonk::prover::prove_with_checkpoints;
+    use crate::util::timing::TimingTree;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        checkpoints: HashMap<ProverPhase, Vec<u8>>,
+        saved: Vec<ProverPhase>,
+    }
+
+    impl CheckpointStore for MemoryStore {
+        fn load(&mut self, phase: ProverPhase) -> Result<Option<Vec<u8>>> {
+            Ok(self.checkpoints.get(&phase).cloned())
+        }
+
+        fn save(&mut self, phase: ProverPhase, checkpoint: &[u8]) -> Result<()> {
+            self.saved.push(phase);
+            self.checkpoints.insert(phase, checkpoint.to_vec());
+            Ok(())
+        }
+    }
+
+    fn circuit(num_squarings: usize, x_value: u32) -> (CircuitData<F, C, D>, PartialWitness<F>) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let mut y = x;
+        for _ in 0..num_squarings {
+            y = builder.square(y);
+        }
+        builder.register_public_input(y);
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u32(x_value)).unwrap();
+        (builder.build::<C>(), pw)
+    }
+
+    #[test]
+    fn test_prove_with_checkpoints() -> Result<()> {
+        let (data, pw) = circuit(100, 3);
+        let prove_inputs = |store: &mut MemoryStore, pw: &PartialWitness<F>| {
+            prove_with_checkpoints(
+                &data.prover_only,
+                &data.common,
+                pw.clone(),
+                &mut TimingTree::default(),
+                store,
+            )
+        };
+        let prove = |store: &mut MemoryStore| prove_inputs(store, &pw);
+
+        let mut store = MemoryStore::default();
+        let proof = prove(&mut store)?;
+        assert_eq!(store.saved, ProverPhase::ALL);
+        data.verify(proof.clone())?;
+
+        // Resume after each phase, as if the prover had been interrupted right after it.
+        for resumed_phases in 1..=ProverPhase::ALL.len() {
+            let mut resumed_store = MemoryStore::default();
+            for phase in &ProverPhase::ALL[..resumed_phases] {
+                resumed_store
+                    .checkpoints
+                    .insert(*phase, store.checkpoints[phase].clone());
+            }
+            let resumed_proof = prove(&mut resumed_store)?;
+            assert_eq!(resumed_store.saved, ProverPhase::ALL[resumed_phases..]);
+            assert_eq!(resumed_proof.public_inputs, proof.public_inputs);
+            assert_eq!(resumed_proof.proof.wires_cap, proof.proof.wires_cap);
+            data.verify(resumed_proof)?;
+        }
+
+        // Checkpoints of other inputs are rejected.
+        let (_, other_inputs) = circuit(100, 5);
+        assert!(prove_inputs(&mut store, &other_inputs).is_err());
+
+        // Checkpoints following other commitments are rejected.
+        let mut other_store = MemoryStore::default();
+        prove_inputs(&mut other_store, &other_inputs)?;
+        for phase in &ProverPhase::ALL[1..] {
+            let mut mixed_store = MemoryStore::default();
+            mixed_store.checkpoints.insert(
+                ProverPhase::WiresCommitment,
+                store.checkpoints[&ProverPhase::WiresCommitment].clone(),
+            );
+            mixed_store
+                .checkpoints
+                .insert(*phase, other_store.checkpoints[phase].clone());
+            assert!(prove(&mut mixed_store).is_err());
+        }
+
+        // Checkpoints of another circuit are rejected.
+        let (other_data, other_pw) = circuit(99, 3);
+        assert!(prove_with_checkpoints(
+            &other_data.prover_only,
+            &other_data.common,
+            other_pw,
+            &mut TimingTree::default(),
+            &mut store,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_directory_checkpoint_store() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("plonky2_checkpoints_{}", std::process::id()));
+        let mut store = DirectoryCheckpointStore::new(&dir)?;
+        assert_eq!(store.load(ProverPhase::WiresCommitment)?, None);
+        store.save(ProverPhase::WiresCommitment, &[1, 2, 3])?;
+        assert_eq!(
+            store.load(ProverPhase::WiresCommitment)?,
+            Some(vec![1, 2, 3])
+        );
+        store.clear()?;
+        assert_eq!(store.load(ProverPhase::WiresCommitment)?, None);
+        std::fs::remove_dir(dir)?;
+        Ok(())
+    }
+}
@@ -47,6 +47,7 @@ use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
 use crate::plonk::prover::prove;
+use crate::plonk::under_constrained::{find_under_constrained, UnderConstrainedReport};
 use crate::plonk::verifier::verify;
 use crate::util::serialization::{
     Buffer, GateSerializer, IoResult, Read, WitnessGeneratorSerializer, Write,
@@ -206,6 +207,15 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         compressed_proof_with_pis.verify(&self.verifier_only, &self.common)
     }
 
+    /// Looks for under-constrained targets in this circuit, as per [`find_under_constrained`].
+    pub fn find_under_constrained(
+        &self,
+        inputs: PartialWitness<F>,
+        num_trials: usize,
+    ) -> Result<UnderConstrainedReport> {
+        find_under_constrained(&self.prover_only, &self.common, inputs, num_trials)
+    }
+
     pub fn compress(
         &self,
         proof: ProofWithPublicInputs<F, C, D>,
@@ -0,0 +1,303 @@
+//! A serializable description of simple circuits, which can be compiled into a [`CircuitData`]
+//! at runtime.
+//!
+//! This allows defining circuits made of common gadgets (arithmetic, Poseidon hashing, range
+//! checks, comparisons) from a data format such as JSON, without writing Rust. For instance, the
+//! following description proves knowledge of a 32-bit preimage of a public Poseidon hash:
+//!
+//! ```json
+//! {
+//!   "num_inputs": 1,
+//!   "operations": [
+//!     { "op": "range_check", "value": 0, "bits": 32 },
+//!     { "op": "hash", "inputs": [0] },
+//!     { "op": "public_input", "value": 1 },
+//!     { "op": "public_input", "value": 2 },
+//!     { "op": "public_input", "value": 3 },
+//!     { "op": "public_input", "value": 4 }
+//!   ]
+//! }
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::target::Target;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+
+/// An operation of a [`CircuitDescription`].
+///
+/// Values are referred to by their index in the list of values of the circuit. This list starts
+/// with the inputs of the circuit, and each operation outputting values appends them to it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CircuitOperation {
+    /// Outputs a constant, given by its canonical representation.
+    Constant {
+        /// The value of the constant.
+        value: u64,
+    },
+    /// Outputs `lhs + rhs`.
+    Add {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+    },
+    /// Outputs `lhs - rhs`.
+    Sub {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+    },
+    /// Outputs `lhs * rhs`.
+    Mul {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+    },
+    /// Outputs the 4 elements of the Poseidon hash of `inputs`, as computed by
+    /// [`hash_n_to_hash_no_pad`](crate::hash::hashing::hash_n_to_hash_no_pad).
+    Hash {
+        /// The values to hash.
+        inputs: Vec<usize>,
+    },
+    /// Outputs 1 if `lhs == rhs`, and 0 otherwise.
+    IsEqual {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+    },
+    /// Asserts that `lhs == rhs`.
+    AssertEqual {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+    },
+    /// Asserts that `value < 2^bits`.
+    RangeCheck {
+        /// The value to range check.
+        value: usize,
+        /// The number of bits of the range.
+        bits: usize,
+    },
+    /// Asserts that `lhs < rhs`, and that both fit in `bits` bits.
+    AssertLessThan {
+        /// The first operand.
+        lhs: usize,
+        /// The second operand.
+        rhs: usize,
+        /// The number of bits of the operands.
+        bits: usize,
+    },
+    /// Registers `value` as a public input.
+    PublicInput {
+        /// The value to make public.
+        value: usize,
+    },
+}
+
+/// A serializable description of a circuit, as a list of [`CircuitOperation`]s applied to
+/// `num_inputs` private inputs.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CircuitDescription {
+    /// The number of private inputs of the circuit, set when proving.
+    pub num_inputs: usize,
+    /// The operations of the circuit, in order.
+    pub operations: Vec<CircuitOperation>,
+}
+
+/// A circuit compiled from a [`CircuitDescription`].
+#[derive(Debug)]
+pub struct DescribedCircuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// The data of the compiled circuit.
+    pub data: CircuitData<F, C, D>,
+    inputs: Vec<Target>,
+}
+
+impl CircuitDescription {
+    /// Compiles this description into a circuit, failing if an operation is ill-formed.
+    pub fn build<F, C, const D: usize>(
+        &self,
+        config: CircuitConfig,
+    ) -> Result<DescribedCircuit<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let inputs = builder.add_virtual_targets(self.num_inputs);
+        let mut values = inputs.clone();
+
+        for (i, operation) in self.operations.iter().enumerate() {
+            let get = |index: usize| -> Result<Target> {
+                ensure!(
+                    index < values.len(),
+                    "Operation {} refers to value {}, but only {} values are defined",
+                    i,
+                    index,
+                    values.len()
+                );
+                Ok(values[index])
+            };
+            match *operation {
+                CircuitOperation::Constant { value } => {
+                    ensure!(
+                        value < F::ORDER,
+                        "Operation {}: constant {} is not canonical",
+                        i,
+                        value
+                    );
+                    values.push(builder.constant(F::from_canonical_u64(value)));
+                }
+                CircuitOperation::Add { lhs, rhs } => {
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    values.push(builder.add(lhs, rhs));
+                }
+                CircuitOperation::Sub { lhs, rhs } => {
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    values.push(builder.sub(lhs, rhs));
+                }
+                CircuitOperation::Mul { lhs, rhs } => {
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    values.push(builder.mul(lhs, rhs));
+                }
+                CircuitOperation::Hash { ref inputs } => {
+                    let inputs = inputs.iter().map(|&x| get(x)).collect::<Result<_>>()?;
+                    let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+                    values.extend(hash.elements);
+                }
+                CircuitOperation::IsEqual { lhs, rhs } => {
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    values.push(builder.is_equal(lhs, rhs).target);
+                }
+                CircuitOperation::AssertEqual { lhs, rhs } => {
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    builder.connect(lhs, rhs);
+                }
+                CircuitOperation::RangeCheck { value, bits } => {
+                    ensure!(
+                        bits < F::BITS,
+                        "Operation {}: cannot range check {} bits",
+                        i,
+                        bits
+                    );
+                    let value = get(value)?;
+                    builder.range_check(value, bits);
+                }
+                CircuitOperation::AssertLessThan { lhs, rhs, bits } => {
+                    // `rhs - lhs - 1` must not wrap around into the range when `lhs >= rhs`.
+                    ensure!(
+                        bits + 1 < F::BITS,
+                        "Operation {}: cannot compare {}-bit values",
+                        i,
+                        bits
+                    );
+                    let (lhs, rhs) = (get(lhs)?, get(rhs)?);
+                    builder.range_check(lhs, bits);
+                    builder.range_check(rhs, bits);
+                    let one = builder.one();
+                    let diff = builder.sub(rhs, lhs);
+                    let diff = builder.sub(diff, one);
+                    builder.range_check(diff, bits);
+                }
+                CircuitOperation::PublicInput { value } => {
+                    let value = get(value)?;
+                    builder.register_public_input(value);
+                }
+            }
+        }
+
+        Ok(DescribedCircuit {
+            data: builder.build::<C>(),
+            inputs,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    DescribedCircuit<F, C, D>
+{
+    /// Proves this circuit with the given private inputs.
+    pub fn prove(&self, inputs: &[F]) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            inputs.len() == self.inputs.len(),
+            "Expected {} inputs, got {}",
+            self.inputs.len(),
+            inputs.len()
+        );
+        let mut pw = PartialWitness::new();
+        for (&target, &value) in self.inputs.iter().zip(inputs) {
+            pw.set_target(target, value)?;
+        }
+        self.data.prove(pw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::hash::hashing::hash_n_to_hash_no_pad;
+    use crate::hash::poseidon::PoseidonPermutation;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_circuit_description() -> Result<()> {
+        // Prove knowledge of `x < y`, such that `x * y` hashes to a public value.
+        let description: CircuitDescription = serde_json::from_str(
+            r#"{
+                "num_inputs": 2,
+                "operations": [
+                    { "op": "assert_less_than", "lhs": 0, "rhs": 1, "bits": 32 },
+                    { "op": "mul", "lhs": 0, "rhs": 1 },
+                    { "op": "hash", "inputs": [2] },
+                    { "op": "public_input", "value": 3 },
+                    { "op": "public_input", "value": 4 },
+                    { "op": "public_input", "value": 5 },
+                    { "op": "public_input", "value": 6 }
+                ]
+            }"#,
+        )?;
+        let circuit = description.build::<F, C, D>(CircuitConfig::standard_recursion_config())?;
+
+        let (x, y) = (F::from_canonical_u32(3), F::from_canonical_u32(5));
+        let proof = circuit.prove(&[x, y])?;
+        let hash = hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&[x * y]);
+        assert_eq!(proof.public_inputs, hash.elements);
+        circuit.data.verify(proof)
+    }
+
+    #[test]
+    fn test_circuit_description_invalid() {
+        let description = CircuitDescription {
+            num_inputs: 1,
+            operations: vec![CircuitOperation::Add { lhs: 0, rhs: 1 }],
+        };
+        assert!(description
+            .build::<F, C, D>(CircuitConfig::standard_recursion_config())
+            .is_err());
+    }
+}
@@ -0,0 +1,423 @@
+//! Export of the wiring of a circuit being built, for visual inspection of small circuits.
+//!
+//! [`CircuitBuilder::circuit_graph`] outputs a [`CircuitGraph`] holding the gates placed so far,
+//! the targets involved in copy constraints, and the copy constraints themselves, labeled with the
+//! context they were added in. Targets are grouped into the partitions induced by the copy
+//! constraints, which become the cycles of the permutation argument. The graph can be rendered in
+//! the DOT format of Graphviz with [`CircuitGraph::to_dot`], e.g. for
+//! `dot -Tsvg circuit.dot -o circuit.svg`, or in GraphML with [`CircuitGraph::to_graphml`].
+//!
+//! Virtual targets which are not involved in any copy constraint, other than constants, are
+//! flagged as unconstrained: their values are set by witness generators, but never checked by any
+//! gate.
+//!
+//! **Note**: the graph reflects the circuit as built so far. Gates and copy constraints added by
+//! [`CircuitBuilder::build`], e.g. for constants or for hashing the public inputs, are not part of
+//! it.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A gate of a [`CircuitGraph`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphGate {
+    /// The row of the gate.
+    pub row: usize,
+    /// The ID of the gate, as given by [`Gate::id`](crate::gates::gate::Gate::id).
+    pub gate: String,
+}
+
+/// A target of a [`CircuitGraph`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphTarget {
+    /// The target.
+    pub target: Target,
+    /// The index of the partition of the target, i.e. of the set of targets it is transitively
+    /// copy constrained to.
+    pub partition: usize,
+    /// Whether the target is a public input.
+    pub public: bool,
+    /// Whether the target is a virtual target which is not involved in any copy constraint, nor
+    /// holds a constant.
+    pub unconstrained: bool,
+}
+
+/// A copy constraint of a [`CircuitGraph`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphCopyConstraint {
+    /// The indices of the two constrained targets in [`CircuitGraph::targets`].
+    pub pair: (usize, usize),
+    /// The context in which the constraint was added.
+    pub label: String,
+}
+
+/// The wiring of a circuit, as output by [`CircuitBuilder::circuit_graph`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitGraph {
+    /// The gates of the circuit.
+    pub gates: Vec<GraphGate>,
+    /// The targets involved in copy constraints, along with the public inputs and the
+    /// unconstrained virtual targets.
+    pub targets: Vec<GraphTarget>,
+    /// The copy constraints between targets.
+    pub copy_constraints: Vec<GraphCopyConstraint>,
+    /// The number of partitions of the targets.
+    pub num_partitions: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Exports the gates, targets and copy constraints of the circuit built so far as a
+    /// [`CircuitGraph`].
+    ///
+    /// If `label_filter` is given, only the copy constraints whose label contains it are exported,
+    /// along with the targets they involve and the gates of these targets.
+    pub fn circuit_graph(&self, label_filter: Option<&str>) -> CircuitGraph {
+        let copy_constraints = self
+            .copy_constraints
+            .iter()
+            .filter(|c| label_filter.is_none_or(|filter| c.name.contains(filter)))
+            .collect::<Vec<_>>();
+        let public_inputs = self.public_inputs.iter().collect::<HashSet<_>>();
+
+        let mut targets = Vec::new();
+        let mut target_indices = HashMap::new();
+        let mut index = |target: Target, targets: &mut Vec<Target>| {
+            *target_indices.entry(target).or_insert_with(|| {
+                targets.push(target);
+                targets.len() - 1
+            })
+        };
+        let pairs = copy_constraints
+            .iter()
+            .map(|c| (index(c.pair.0, &mut targets), index(c.pair.1, &mut targets)))
+            .collect::<Vec<_>>();
+        let num_constrained = targets.len();
+        if label_filter.is_none() {
+            for &target in &self.public_inputs {
+                index(target, &mut targets);
+            }
+            for index_ in 0..self.virtual_target_index {
+                index(Target::VirtualTarget { index: index_ }, &mut targets);
+            }
+        }
+
+        // Union-find over the targets, with path halving.
+        let mut parents = (0..targets.len()).collect::<Vec<_>>();
+        let find = |parents: &mut Vec<usize>, mut i: usize| {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        };
+        for &(i, j) in &pairs {
+            let (root_i, root_j) = (find(&mut parents, i), find(&mut parents, j));
+            parents[root_i] = root_j;
+        }
+        let mut partitions = HashMap::new();
+        let targets = targets
+            .into_iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let root = find(&mut parents, i);
+                let num_partitions = partitions.len();
+                GraphTarget {
+                    target,
+                    partition: *partitions.entry(root).or_insert(num_partitions),
+                    public: public_inputs.contains(&target),
+                    unconstrained: i >= num_constrained
+                        && matches!(target, Target::VirtualTarget { .. })
+                        && !self.targets_to_constants.contains_key(&target),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let rows = targets
+            .iter()
+            .filter_map(|t| match t.target {
+                Target::Wire(Wire { row, .. }) => Some(row),
+                Target::VirtualTarget { .. } => None,
+            })
+            .collect::<HashSet<_>>();
+        let gates = self
+            .gate_instances
+            .iter()
+            .enumerate()
+            .filter(|(row, _)| label_filter.is_none() || rows.contains(row))
+            .map(|(row, instance)| GraphGate {
+                row,
+                gate: instance.gate_ref.0.id(),
+            })
+            .collect();
+
+        CircuitGraph {
+            gates,
+            targets,
+            copy_constraints: pairs
+                .into_iter()
+                .zip(copy_constraints)
+                .map(|(pair, c)| GraphCopyConstraint {
+                    pair,
+                    label: c.name.clone(),
+                })
+                .collect(),
+            num_partitions: partitions.len(),
+        }
+    }
+}
+
+impl CircuitGraph {
+    /// Returns the unconstrained virtual targets of this graph.
+    pub fn unconstrained_targets(&self) -> Vec<Target> {
+        self.targets
+            .iter()
+            .filter(|t| t.unconstrained)
+            .map(|t| t.target)
+            .collect()
+    }
+
+    /// Returns the row of the gate node of the given target, if it is a wire of a gate in this
+    /// graph.
+    fn gate_of(&self, target: Target) -> Option<usize> {
+        match target {
+            Target::Wire(Wire { row, .. }) => {
+                self.gates.iter().any(|g| g.row == row).then_some(row)
+            }
+            Target::VirtualTarget { .. } => None,
+        }
+    }
+
+    /// Renders this graph in the DOT format of Graphviz.
+    ///
+    /// Gates are boxes, and targets ellipses linked to their gate by a dotted edge. Copy
+    /// constraints are solid edges labeled with their context. Public inputs have a double
+    /// outline, and unconstrained targets are red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph circuit {\n");
+        for gate in &self.gates {
+            let label = format!("{}: {}", gate.row, gate.gate);
+            writeln!(
+                dot,
+                "  g{} [shape=box, label=\"{}\"];",
+                gate.row,
+                escape_dot(&label)
+            )
+            .unwrap();
+        }
+        for (i, target) in self.targets.iter().enumerate() {
+            let mut attributes = format!(
+                "label=\"{}\\npartition {}\"",
+                target_name(target.target),
+                target.partition
+            );
+            if target.public {
+                attributes += ", peripheries=2";
+            }
+            if target.unconstrained {
+                attributes += ", color=red, fontcolor=red";
+            }
+            writeln!(dot, "  t{} [{}];", i, attributes).unwrap();
+            if let Some(row) = self.gate_of(target.target) {
+                writeln!(dot, "  t{} -- g{} [style=dotted];", i, row).unwrap();
+            }
+        }
+        for c in &self.copy_constraints {
+            writeln!(
+                dot,
+                "  t{} -- t{} [label=\"{}\"];",
+                c.pair.0,
+                c.pair.1,
+                escape_dot(&c.label)
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders this graph in GraphML.
+    ///
+    /// Nodes and edges have a `kind` attribute, which is `gate` or `target` for nodes, and `wire`
+    /// (between a target and its gate) or `copy` for edges. Gates, targets and copy constraints
+    /// have a `label`, and targets have `partition`, `public` and `unconstrained` attributes.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"kind\" for=\"all\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"label\" for=\"all\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"partition\" for=\"node\" attr.name=\"partition\" attr.type=\"int\"/>\n",
+            "  <key id=\"public\" for=\"node\" attr.name=\"public\" attr.type=\"boolean\"/>\n",
+            "  <key id=\"unconstrained\" for=\"node\" attr.name=\"unconstrained\" ",
+            "attr.type=\"boolean\"/>\n",
+            "  <graph id=\"circuit\" edgedefault=\"undirected\">\n",
+        ));
+        for gate in &self.gates {
+            writeln!(
+                xml,
+                "    <node id=\"g{}\"><data key=\"kind\">gate</data>\
+                 <data key=\"label\">{}: {}</data></node>",
+                gate.row,
+                gate.row,
+                escape_xml(&gate.gate)
+            )
+            .unwrap();
+        }
+        for (i, target) in self.targets.iter().enumerate() {
+            writeln!(
+                xml,
+                "    <node id=\"t{}\"><data key=\"kind\">target</data>\
+                 <data key=\"label\">{}</data><data key=\"partition\">{}</data>\
+                 <data key=\"public\">{}</data><data key=\"unconstrained\">{}</data></node>",
+                i,
+                target_name(target.target),
+                target.partition,
+                target.public,
+                target.unconstrained
+            )
+            .unwrap();
+            if let Some(row) = self.gate_of(target.target) {
+                writeln!(
+                    xml,
+                    "    <edge source=\"t{}\" target=\"g{}\"><data key=\"kind\">wire</data></edge>",
+                    i, row
+                )
+                .unwrap();
+            }
+        }
+        for c in &self.copy_constraints {
+            writeln!(
+                xml,
+                "    <edge source=\"t{}\" target=\"t{}\"><data key=\"kind\">copy</data>\
+                 <data key=\"label\">{}</data></edge>",
+                c.pair.0,
+                c.pair.1,
+                escape_xml(&c.label)
+            )
+            .unwrap();
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+fn target_name(target: Target) -> String {
+    match target {
+        Target::Wire(Wire { row, column }) => format!("wire({}, {})", row, column),
+        Target::VirtualTarget { index } => format!("virtual({})", index),
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::with_context;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn builder() -> (CircuitBuilder<F, D>, [Target; 4]) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let [x, y, hint, unused] = [(); 4].map(|_| builder.add_virtual_target());
+        builder.register_public_input(x);
+        let product = with_context!(builder, "product", builder.mul(x, y));
+        with_context!(builder, "check", builder.connect(product, hint));
+        (builder, [x, y, hint, unused])
+    }
+
+    #[test]
+    fn test_circuit_graph() {
+        let (builder, [x, y, hint, unused]) = builder();
+        let graph = builder.circuit_graph(None);
+
+        assert_eq!(graph.gates.len(), 1);
+        assert!(graph.gates[0].gate.starts_with("ArithmeticGate"));
+        assert_eq!(graph.unconstrained_targets(), vec![unused]);
+
+        let partition = |target| {
+            graph
+                .targets
+                .iter()
+                .find(|t| t.target == target)
+                .unwrap()
+                .partition
+        };
+        assert_ne!(partition(x), partition(y));
+        assert_ne!(partition(hint), partition(unused));
+        // The copy constraints don't form any cycle.
+        assert_eq!(
+            graph.num_partitions,
+            graph.targets.len() - graph.copy_constraints.len()
+        );
+        assert!(graph.targets.iter().any(|t| t.target == x && t.public));
+        assert!(graph
+            .copy_constraints
+            .iter()
+            .any(|c| c.label.ends_with("check")));
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("graph circuit {\n"));
+        assert!(dot.contains("g0 [shape=box, label=\"0: ArithmeticGate"));
+        assert!(dot.contains("label=\"virtual(3)\\npartition"));
+        assert!(dot.contains("color=red"));
+        assert_eq!(
+            dot.matches(" -- ").count(),
+            2 * graph.copy_constraints.len()
+        );
+
+        let graphml = graph.to_graphml();
+        assert_eq!(graphml.matches("<node ").count(), 1 + graph.targets.len());
+        assert_eq!(
+            graphml.matches("<data key=\"kind\">copy</data>").count(),
+            graph.copy_constraints.len()
+        );
+        assert_eq!(
+            graphml
+                .matches("<data key=\"unconstrained\">true</data>")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_circuit_graph_label_filter() {
+        let (builder, [_, _, hint, _]) = builder();
+        let graph = builder.circuit_graph(Some("check"));
+
+        assert_eq!(graph.copy_constraints.len(), 1);
+        assert_eq!(graph.targets.len(), 2);
+        assert!(graph.targets.iter().any(|t| t.target == hint));
+        assert_eq!(graph.num_partitions, 1);
+        assert!(graph.unconstrained_targets().is_empty());
+        assert_eq!(graph.gates.len(), 1);
+
+        assert!(builder.circuit_graph(Some("missing")).gates.is_empty());
+    }
+}
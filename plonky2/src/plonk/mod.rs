@@ -3,8 +3,11 @@
 //! This module also defines the [CircuitBuilder](circuit_builder::CircuitBuilder)
 //! structure, used to build custom plonky2 circuits satisfying arbitrary statements.
 
+pub mod checkpoint;
 pub mod circuit_builder;
 pub mod circuit_data;
+pub mod circuit_description;
+pub mod circuit_graph;
 pub mod config;
 pub(crate) mod copy_constraint;
 mod get_challenges;
@@ -12,7 +15,9 @@ pub(crate) mod permutation_argument;
 pub mod plonk_common;
 pub mod proof;
 pub mod prover;
+pub mod under_constrained;
 mod validate_shape;
 pub(crate) mod vanishing_poly;
 pub mod vars;
 pub mod verifier;
+pub mod verifier_export;
@@ -20,10 +20,15 @@ use crate::gates::lookup::LookupGate;
 use crate::gates::lookup_table::LookupTableGate;
 use crate::gates::selectors::LookupSelectors;
 use crate::hash::hash_types::RichField;
+use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::challenger::Challenger;
-use crate::iop::generator::generate_partial_witness;
+use crate::iop::generator::{
+    generate_partial_witness, generate_partial_witness_recording_outputs,
+    regenerate_partial_witness, GeneratedWitness,
+};
 use crate::iop::target::Target;
 use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::checkpoint::{hash_inputs, Checkpoint, CheckpointStore, ProverPhase};
 use crate::plonk::circuit_builder::NUM_COINS_LOOKUP;
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
@@ -32,9 +37,10 @@ use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
 use crate::plonk::vanishing_poly::{eval_vanishing_poly_base_batch, get_lut_poly};
 use crate::plonk::vars::EvaluationVarsBaseBatch;
 use crate::timed;
+use crate::util::column_storage::ColumnStorage;
 use crate::util::partial_products::{partial_products_and_z_gx, quotient_chunk_products};
 use crate::util::timing::TimingTree;
-use crate::util::{log2_ceil, transpose};
+use crate::util::{log2_ceil, par_shifted_powers, transpose};
 
 /// Set all the lookup gate wires (including multiplicities) and pad unused LU slots.
 /// Warning: rows are in descending order: the first gate to appear is the last LU gate, and
@@ -112,12 +118,42 @@ pub fn set_lookup_wires<
     Ok(())
 }
 
+/// The number of rows of the LDEs transposed at a time when they are kept out of memory.
+const STORAGE_CHUNK_SIZE: usize = 1 << 12;
+
 pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
     inputs: PartialWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
+    prove_with_storage(
+        prover_data,
+        common_data,
+        inputs,
+        &ColumnStorage::Memory,
+        timing,
+    )
+}
+
+/// Like [`prove`], but keeps the LDEs and Merkle leaves of the wires, `Z`s and quotient
+/// commitments in `storage`. With [`ColumnStorage::Mapped`], they are kept out of memory; see
+/// [`mmap`](crate::util::mmap).
+pub fn prove_with_storage<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    storage: &ColumnStorage,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>>
 where
     C::Hasher: Hasher<F>,
     C::InnerHasher: Hasher<F>,
@@ -135,7 +171,18 @@ where
     debug!("    NOTE if you want to print partition_witness.values, go into plonk/prover.rs");
     //debug!("{:?}", partition_witness.values);
 
-    prove_with_partition_witness(prover_data, common_data, partition_witness, timing)
+    let (public_inputs, witness, wires_commitment) =
+        commit_to_wires(prover_data, common_data, partition_witness, storage, timing)?;
+    prove_from_wires_commitment(
+        prover_data,
+        common_data,
+        public_inputs,
+        Some(&witness),
+        &wires_commitment,
+        storage,
+        timing,
+        None,
+    )
 }
 
 pub fn prove_with_partition_witness<
@@ -145,23 +192,356 @@ pub fn prove_with_partition_witness<
 >(
     prover_data: &ProverOnlyCircuitData<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
-    mut partition_witness: PartitionWitness<F>,
+    partition_witness: PartitionWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>>
 where
     C::Hasher: Hasher<F>,
     C::InnerHasher: Hasher<F>,
 {
-    let has_lookup = !common_data.luts.is_empty();
+    let (public_inputs, witness, wires_commitment) = commit_to_wires(
+        prover_data,
+        common_data,
+        partition_witness,
+        &ColumnStorage::Memory,
+        timing,
+    )?;
+    prove_from_wires_commitment(
+        prover_data,
+        common_data,
+        public_inputs,
+        Some(&witness),
+        &wires_commitment,
+        &ColumnStorage::Memory,
+        timing,
+        None,
+    )
+}
+
+/// Like [`prove`], but saves the result of each phase of the prover to `store`, and resumes
+/// from the phases already saved there. See [`checkpoint`](crate::plonk::checkpoint).
+pub fn prove_with_checkpoints<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+    store: &mut dyn CheckpointStore,
+) -> Result<ProofWithPublicInputs<F, C, D>>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
+    let circuit_digest = &prover_data.circuit_digest;
+    let inputs_hash = hash_inputs::<F, C::Hasher>(&inputs);
+    let phase = ProverPhase::WiresCommitment;
+    let (public_inputs, witness, wires_commitment) =
+        match Checkpoint::<F, C, D>::load(store, phase, circuit_digest, Some(&inputs_hash), &[])? {
+            Some(checkpoint) => (checkpoint.public_inputs, None, checkpoint.commitment),
+            None => {
+                let partition_witness = timed!(
+                    timing,
+                    &format!("run {} generators", prover_data.generators.len()),
+                    generate_partial_witness(inputs, prover_data, common_data)?
+                );
+                let (public_inputs, witness, commitment) = commit_to_wires(
+                    prover_data,
+                    common_data,
+                    partition_witness,
+                    &ColumnStorage::Memory,
+                    timing,
+                )?;
+                let checkpoint = Checkpoint {
+                    inputs_hash: Some(inputs_hash),
+                    preceding_caps: vec![],
+                    public_inputs,
+                    commitment,
+                };
+                store.save(phase, &checkpoint.to_bytes(circuit_digest))?;
+                (
+                    checkpoint.public_inputs,
+                    Some(witness),
+                    checkpoint.commitment,
+                )
+            }
+        };
+
+    prove_from_wires_commitment(
+        prover_data,
+        common_data,
+        public_inputs,
+        witness.as_ref(),
+        &wires_commitment,
+        &ColumnStorage::Memory,
+        timing,
+        Some(store),
+    )
+}
+
+/// The intermediate results of a proof, which [`prove_incremental`] reuses to prove the same
+/// circuit for a slightly different witness.
+#[derive(Debug)]
+pub struct ProverArtifacts<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    inputs: PartialWitness<F>,
+    generated_witness: GeneratedWitness<F>,
+    witness: MatrixWitness<F>,
+    wires_commitment: PolynomialBatch<F, C, D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverArtifacts<F, C, D>
+{
+    /// The inputs the proof was generated for.
+    pub fn inputs(&self) -> &PartialWitness<F> {
+        &self.inputs
+    }
+}
+
+/// Like [`prove`], but also returns the artifacts needed by [`prove_incremental`] to prove the
+/// circuit again for slightly different inputs.
+#[allow(clippy::type_complexity)]
+pub fn prove_with_artifacts<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<(ProofWithPublicInputs<F, C, D>, ProverArtifacts<F, C, D>)>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
+    let (partition_witness, generator_outputs) = timed!(
+        timing,
+        &format!("run {} generators", prover_data.generators.len()),
+        generate_partial_witness_recording_outputs(inputs.clone(), prover_data, common_data)?
+    );
+    let generated_witness = GeneratedWitness {
+        values: partition_witness.values.clone(),
+        generator_outputs,
+    };
+    let (public_inputs, witness, wires_commitment) = commit_to_wires(
+        prover_data,
+        common_data,
+        partition_witness,
+        &ColumnStorage::Memory,
+        timing,
+    )?;
+
+    let proof = prove_from_wires_commitment(
+        prover_data,
+        common_data,
+        public_inputs,
+        Some(&witness),
+        &wires_commitment,
+        &ColumnStorage::Memory,
+        timing,
+        None,
+    )?;
+    let artifacts = ProverArtifacts {
+        inputs,
+        generated_witness,
+        witness,
+        wires_commitment,
+    };
+    Ok((proof, artifacts))
+}
+
+/// Proves the circuit for the inputs of `prev_artifacts`, updated with the values of `delta`.
+///
+/// Only the generators depending on the targets of `delta` are re-run, and, unless the circuit is
+/// zero-knowledge, the wire polynomials whose values are unchanged are not interpolated and
+/// extended again. The targets of `delta` must be inputs of the circuit, i.e. targets which are not
+/// populated by generators, otherwise this fails. Circuits with lookups regenerate the whole
+/// witness, as the lookup multiplicities depend on all the lookups.
+///
+/// The challenges depend on the wires commitment, so the rest of the proof is computed from
+/// scratch. For zero-knowledge circuits, the blinding values of
+/// [`RandomValueGenerator`](crate::iop::generator::RandomValueGenerator)s are sampled again, so
+/// that they aren't shared between proofs; other values generated without depending on other
+/// targets are reused.
+#[allow(clippy::type_complexity)]
+pub fn prove_incremental<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    prev_artifacts: &ProverArtifacts<F, C, D>,
+    delta: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<(ProofWithPublicInputs<F, C, D>, ProverArtifacts<F, C, D>)>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
     let config = &common_data.config;
-    let num_challenges = config.num_challenges;
-    let quotient_degree = common_data.quotient_degree();
-    let degree = common_data.degree();
+    ensure!(
+        prev_artifacts.witness.wire_values.len() == config.num_wires
+            && prev_artifacts.wires_commitment.degree_log == common_data.degree_bits(),
+        "The artifacts were produced for another circuit"
+    );
+    let mut is_generated = vec![false; prev_artifacts.generated_witness.values.len()];
+    for &rep in prev_artifacts
+        .generated_witness
+        .generator_outputs
+        .iter()
+        .flatten()
+    {
+        is_generated[rep] = true;
+    }
+    for &target in delta.target_values.keys() {
+        let rep =
+            prover_data.representative_map[target.index(config.num_wires, common_data.degree())];
+        ensure!(
+            !is_generated[rep],
+            "The target {:?} of the delta is populated by a generator",
+            target
+        );
+    }
+
+    let mut inputs = prev_artifacts.inputs.clone();
+    inputs.target_values.extend(&delta.target_values);
+
+    let (partition_witness, generator_outputs) = if common_data.luts.is_empty() {
+        timed!(
+            timing,
+            "re-run affected generators",
+            regenerate_partial_witness(
+                &prev_artifacts.generated_witness,
+                &delta,
+                prover_data,
+                common_data
+            )?
+        )
+    } else {
+        timed!(
+            timing,
+            &format!("run {} generators", prover_data.generators.len()),
+            generate_partial_witness_recording_outputs(inputs.clone(), prover_data, common_data)?
+        )
+    };
+    let generated_witness = GeneratedWitness {
+        values: partition_witness.values.clone(),
+        generator_outputs,
+    };
+    let (public_inputs, witness) =
+        complete_witness(prover_data, common_data, partition_witness, timing)?;
+
+    let wires_values = wire_polynomials(&witness, timing);
+    let blinding = config.zero_knowledge && PlonkOracle::WIRES.blinding;
+    let wires_commitment = if blinding {
+        // Reusing the salts would reveal which columns are unchanged.
+        timed!(
+            timing,
+            "compute wires commitment",
+            PolynomialBatch::<F, C, D>::from_values(
+                wires_values,
+                config.fri_config.rate_bits,
+                blinding,
+                config.fri_config.cap_height,
+                timing,
+                prover_data.fft_root_table.as_ref(),
+            )
+        )
+    } else {
+        let unchanged: Vec<bool> = witness
+            .wire_values
+            .par_iter()
+            .zip(&prev_artifacts.witness.wire_values)
+            .map(|(column, prev_column)| column == prev_column)
+            .collect();
+        timed!(
+            timing,
+            &format!(
+                "compute wires commitment, reusing {} of {} columns",
+                unchanged.iter().filter(|&&u| u).count(),
+                unchanged.len()
+            ),
+            PolynomialBatch::<F, C, D>::from_values_reusing(
+                wires_values,
+                &unchanged,
+                &prev_artifacts.wires_commitment,
+                config.fri_config.cap_height,
+                timing,
+                prover_data.fft_root_table.as_ref(),
+            )
+        )
+    };
+
+    let proof = prove_from_wires_commitment(
+        prover_data,
+        common_data,
+        public_inputs,
+        Some(&witness),
+        &wires_commitment,
+        &ColumnStorage::Memory,
+        timing,
+        None,
+    )?;
+    let artifacts = ProverArtifacts {
+        inputs,
+        generated_witness,
+        witness,
+        wires_commitment,
+    };
+    Ok((proof, artifacts))
+}
+
+/// Computes the full witness and commits to the wire polynomials, returning the public inputs,
+/// the witness and the commitment.
+#[allow(clippy::type_complexity)]
+fn commit_to_wires<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    partition_witness: PartitionWitness<F>,
+    storage: &ColumnStorage,
+    timing: &mut TimingTree,
+) -> Result<(Vec<F>, MatrixWitness<F>, PolynomialBatch<F, C, D>)> {
+    let config = &common_data.config;
+
+    let (public_inputs, witness) =
+        complete_witness(prover_data, common_data, partition_witness, timing)?;
 
+    let wires_values = wire_polynomials(&witness, timing);
+
+    let wires_commitment = timed!(
+        timing,
+        "compute wires commitment",
+        PolynomialBatch::<F, C, D>::from_values_with_storage(
+            wires_values,
+            config.fri_config.rate_bits,
+            config.zero_knowledge && PlonkOracle::WIRES.blinding,
+            config.fri_config.cap_height,
+            timing,
+            prover_data.fft_root_table.as_ref(),
+            storage,
+            STORAGE_CHUNK_SIZE,
+        )?
+    );
+
+    Ok((public_inputs, witness, wires_commitment))
+}
+
+/// Sets the lookup wires and computes the full witness, returning the public inputs and the
+/// witness.
+pub(crate) fn complete_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    mut partition_witness: PartitionWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<(Vec<F>, MatrixWitness<F>)> {
     set_lookup_wires(prover_data, common_data, &mut partition_witness)?;
 
     let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
-    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
 
     println!("About to create matrix witness!");
     println!(
@@ -175,7 +555,14 @@ where
         partition_witness.full_witness()
     );
 
-    let wires_values: Vec<PolynomialValues<F>> = timed!(
+    Ok((public_inputs, witness))
+}
+
+fn wire_polynomials<F: Field>(
+    witness: &MatrixWitness<F>,
+    timing: &mut TimingTree,
+) -> Vec<PolynomialValues<F>> {
+    timed!(
         timing,
         "compute wire polynomials",
         witness
@@ -183,20 +570,64 @@ where
             .par_iter()
             .map(|column| PolynomialValues::new(column.clone()))
             .collect()
-    );
+    )
+}
 
-    let wires_commitment = timed!(
-        timing,
-        "compute wires commitment",
-        PolynomialBatch::<F, C, D>::from_values(
-            wires_values,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::WIRES.blinding,
-            config.fri_config.cap_height,
-            timing,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
+/// Runs `compute`, unless the result of `phase` can be loaded from `store`. If it is computed,
+/// the result is saved to `store`. The checkpoint is bound to the caps of the commitments of the
+/// preceding phases, `preceding_caps`.
+fn checkpointed<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    store: &mut Option<&mut dyn CheckpointStore>,
+    phase: ProverPhase,
+    circuit_digest: &<C::Hasher as Hasher<F>>::Hash,
+    preceding_caps: &[&MerkleCap<F, C::Hasher>],
+    compute: impl FnOnce() -> Result<PolynomialBatch<F, C, D>>,
+) -> Result<PolynomialBatch<F, C, D>> {
+    let Some(store) = store.as_deref_mut() else {
+        return compute();
+    };
+    if let Some(checkpoint) = Checkpoint::load(store, phase, circuit_digest, None, preceding_caps)?
+    {
+        return Ok(checkpoint.commitment);
+    }
+    let checkpoint = Checkpoint {
+        inputs_hash: None,
+        preceding_caps: preceding_caps.iter().map(|&cap| cap.clone()).collect(),
+        public_inputs: vec![],
+        commitment: compute()?,
+    };
+    store.save(phase, &checkpoint.to_bytes(circuit_digest))?;
+    Ok(checkpoint.commitment)
+}
+
+/// Proves the circuit once the wires are committed to. If `witness` is `None`, it is recovered
+/// from `wires_commitment` when needed.
+fn prove_from_wires_commitment<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    public_inputs: Vec<F>,
+    witness: Option<&MatrixWitness<F>>,
+    wires_commitment: &PolynomialBatch<F, C, D>,
+    storage: &ColumnStorage,
+    timing: &mut TimingTree,
+    mut store: Option<&mut dyn CheckpointStore>,
+) -> Result<ProofWithPublicInputs<F, C, D>>
+where
+    C::Hasher: Hasher<F>,
+    C::InnerHasher: Hasher<F>,
+{
+    let has_lookup = !common_data.luts.is_empty();
+    let config = &common_data.config;
+    let num_challenges = config.num_challenges;
+    let quotient_degree = common_data.quotient_degree();
+    let degree = common_data.degree();
+    let circuit_digest = &prover_data.circuit_digest;
+
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
 
     let mut challenger = Challenger::<F, C::Hasher>::new();
 
@@ -229,89 +660,137 @@ where
         common_data.quotient_degree_factor < common_data.config.num_routed_wires,
         "When the number of routed wires is smaller that the degree, we should change the logic to avoid computing partial products."
     );
-    let mut partial_products_and_zs = timed!(
-        timing,
-        "compute partial products",
-        all_wires_permutation_partial_products(&witness, &betas, &gammas, prover_data, common_data)
-    );
-
-    // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
-    let plonk_z_vecs = partial_products_and_zs
-        .iter_mut()
-        .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
-        .collect();
-    let zs_partial_products = [plonk_z_vecs, partial_products_and_zs.concat()].concat();
-
-    // All lookup polys: RE and partial SLDCs.
-    let lookup_polys =
-        compute_all_lookup_polys(&witness, &deltas, prover_data, common_data, has_lookup);
+    let partial_products_zs_and_lookup_commitment = checkpointed(
+        &mut store,
+        ProverPhase::ZsPartialProductsCommitment,
+        circuit_digest,
+        &[&wires_commitment.merkle_tree.cap],
+        || {
+            let recovered_witness;
+            let witness = match witness {
+                Some(witness) => witness,
+                None => {
+                    recovered_witness = timed!(
+                        timing,
+                        "recover witness from wires commitment",
+                        MatrixWitness {
+                            wire_values: wires_commitment
+                                .polynomials
+                                .par_iter()
+                                .map(|poly| poly.clone().fft().values)
+                                .collect(),
+                        }
+                    );
+                    &recovered_witness
+                }
+            };
 
-    let zs_partial_products_lookups = if has_lookup {
-        [zs_partial_products, lookup_polys].concat()
-    } else {
-        zs_partial_products
-    };
+            let mut partial_products_and_zs = timed!(
+                timing,
+                "compute partial products",
+                all_wires_permutation_partial_products(
+                    witness,
+                    &betas,
+                    &gammas,
+                    prover_data,
+                    common_data
+                )
+            );
 
-    let partial_products_zs_and_lookup_commitment = timed!(
-        timing,
-        "commit to partial products, Z's and, if any, lookup polynomials",
-        PolynomialBatch::from_values(
-            zs_partial_products_lookups,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
-            config.fri_config.cap_height,
-            timing,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
+            // Z is expected at the front of our batch; see `zs_range` and `partial_products_range`.
+            let plonk_z_vecs = partial_products_and_zs
+                .iter_mut()
+                .map(|partial_products_and_z| partial_products_and_z.pop().unwrap())
+                .collect();
+            let zs_partial_products = [plonk_z_vecs, partial_products_and_zs.concat()].concat();
+
+            // All lookup polys: RE and partial SLDCs.
+            let lookup_polys =
+                compute_all_lookup_polys(witness, &deltas, prover_data, common_data, has_lookup);
+
+            let zs_partial_products_lookups = if has_lookup {
+                [zs_partial_products, lookup_polys].concat()
+            } else {
+                zs_partial_products
+            };
+
+            timed!(
+                timing,
+                "commit to partial products, Z's and, if any, lookup polynomials",
+                PolynomialBatch::from_values_with_storage(
+                    zs_partial_products_lookups,
+                    config.fri_config.rate_bits,
+                    config.zero_knowledge && PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
+                    config.fri_config.cap_height,
+                    timing,
+                    prover_data.fft_root_table.as_ref(),
+                    storage,
+                    STORAGE_CHUNK_SIZE,
+                )
+            )
+        },
+    )?;
 
     challenger.observe_cap::<C::Hasher>(&partial_products_zs_and_lookup_commitment.merkle_tree.cap);
 
     let alphas = challenger.get_n_challenges(num_challenges);
 
-    let quotient_polys = timed!(
-        timing,
-        "compute quotient polys",
-        compute_quotient_polys::<F, C, D>(
-            common_data,
-            prover_data,
-            &public_inputs_hash,
-            &wires_commitment,
-            &partial_products_zs_and_lookup_commitment,
-            &betas,
-            &gammas,
-            &deltas,
-            &alphas,
-        )
-    );
+    let quotient_polys_commitment = checkpointed(
+        &mut store,
+        ProverPhase::QuotientCommitment,
+        circuit_digest,
+        &[
+            &wires_commitment.merkle_tree.cap,
+            &partial_products_zs_and_lookup_commitment.merkle_tree.cap,
+        ],
+        || {
+            let quotient_polys = timed!(
+                timing,
+                "compute quotient polys",
+                compute_quotient_polys::<F, C, D>(
+                    common_data,
+                    prover_data,
+                    &public_inputs_hash,
+                    wires_commitment,
+                    &partial_products_zs_and_lookup_commitment,
+                    &betas,
+                    &gammas,
+                    &deltas,
+                    &alphas,
+                )
+            );
 
-    let all_quotient_poly_chunks: Vec<PolynomialCoeffs<F>> = timed!(
-        timing,
-        "split up quotient polys",
-        quotient_polys
-            .into_par_iter()
-            .flat_map(|mut quotient_poly| {
-                quotient_poly.trim_to_len(quotient_degree).expect(
-                    "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
-                );
-                // Split quotient into degree-n chunks.
-                quotient_poly.chunks(degree)
-            })
-            .collect()
-    );
+            let all_quotient_poly_chunks: Vec<PolynomialCoeffs<F>> = timed!(
+                timing,
+                "split up quotient polys",
+                quotient_polys
+                    .into_par_iter()
+                    .flat_map(|mut quotient_poly| {
+                        quotient_poly.trim_to_len(quotient_degree).expect(
+                            "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
+                        );
+                        // Split quotient into degree-n chunks.
+                        quotient_poly.chunks(degree)
+                    })
+                    .collect()
+            );
 
-    let quotient_polys_commitment = timed!(
-        timing,
-        "commit to quotient polys",
-        PolynomialBatch::<F, C, D>::from_coeffs(
-            all_quotient_poly_chunks,
-            config.fri_config.rate_bits,
-            config.zero_knowledge && PlonkOracle::QUOTIENT.blinding,
-            config.fri_config.cap_height,
-            timing,
-            prover_data.fft_root_table.as_ref(),
-        )
-    );
+            timed!(
+                timing,
+                "commit to quotient polys",
+                PolynomialBatch::<F, C, D>::from_coeffs_with_storage(
+                    all_quotient_poly_chunks,
+                    config.fri_config.rate_bits,
+                    config.zero_knowledge && PlonkOracle::QUOTIENT.blinding,
+                    config.fri_config.cap_height,
+                    timing,
+                    prover_data.fft_root_table.as_ref(),
+                    storage,
+                    STORAGE_CHUNK_SIZE,
+                )
+            )
+        },
+    )?;
 
     challenger.observe_cap::<C::Hasher>(&quotient_polys_commitment.merkle_tree.cap);
 
@@ -332,7 +811,7 @@ where
             zeta,
             g,
             &prover_data.constants_sigmas_commitment,
-            &wires_commitment,
+            wires_commitment,
             &partial_products_zs_and_lookup_commitment,
             &quotient_polys_commitment,
             common_data
@@ -348,7 +827,7 @@ where
             &instance,
             &[
                 &prover_data.constants_sigmas_commitment,
-                &wires_commitment,
+                wires_commitment,
                 &partial_products_zs_and_lookup_commitment,
                 &quotient_polys_commitment,
             ],
@@ -361,7 +840,7 @@ where
     );
 
     let proof = Proof::<F, C, D> {
-        wires_cap: wires_commitment.merkle_tree.cap,
+        wires_cap: wires_commitment.merkle_tree.cap.clone(),
         plonk_zs_partial_products_cap: partial_products_zs_and_lookup_commitment.merkle_tree.cap,
         quotient_polys_cap: quotient_polys_commitment.merkle_tree.cap,
         openings,
@@ -652,7 +1131,8 @@ fn compute_quotient_polys<
     // steps away since we work on an LDE of degree `max_filtered_constraint_degree`.
     let next_step = 1 << quotient_degree_bits;
 
-    let points = F::two_adic_subgroup(common_data.degree_bits() + quotient_degree_bits);
+    let lde_bits = common_data.degree_bits() + quotient_degree_bits;
+    let points = par_shifted_powers(F::primitive_root_of_unity(lde_bits), F::ONE, 1 << lde_bits);
     let lde_size = points.len();
 
     let z_h_on_coset = ZeroPolyOnCoset::new(common_data.degree_bits(), quotient_degree_bits);
@@ -825,3 +1305,148 @@ fn compute_quotient_polys<
         .map(|values| values.coset_ifft(F::coset_shift()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A circuit proving `x^(2^100)` and `y^(2^100)`, whose inputs change independently.
+    fn circuit(config: CircuitConfig) -> (CircuitData<F, C, D>, Target, Target) {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        for input in [x, y] {
+            let mut output = input;
+            for _ in 0..100 {
+                output = builder.square(output);
+            }
+            builder.register_public_input(output);
+        }
+        (builder.build::<C>(), x, y)
+    }
+
+    fn test_prove_incremental_with_config(config: CircuitConfig) -> Result<()> {
+        let (data, x, y) = circuit(config);
+        let inputs = |x_value, y_value| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(x, F::from_canonical_u32(x_value)).unwrap();
+            pw.set_target(y, F::from_canonical_u32(y_value)).unwrap();
+            pw
+        };
+
+        let (proof, artifacts) = prove_with_artifacts(
+            &data.prover_only,
+            &data.common,
+            inputs(3, 5),
+            &mut TimingTree::default(),
+        )?;
+        data.verify(proof)?;
+
+        let mut delta = PartialWitness::new();
+        delta.set_target(y, F::from_canonical_u32(7))?;
+        let prev_artifacts = artifacts;
+        let (proof, artifacts) = prove_incremental(
+            &data.prover_only,
+            &data.common,
+            &prev_artifacts,
+            delta,
+            &mut TimingTree::default(),
+        )?;
+        let expected = data.prove(inputs(3, 7))?;
+        assert_eq!(proof.public_inputs, expected.public_inputs);
+        if data.common.config.zero_knowledge {
+            // The blinding values are sampled again.
+            let blinding_reps = data
+                .prover_only
+                .generators
+                .iter()
+                .zip(&prev_artifacts.generated_witness.generator_outputs)
+                .filter(|(generator, _)| generator.is_random_value())
+                .flat_map(|(_, outputs)| outputs.iter().copied())
+                .collect::<Vec<_>>();
+            assert!(!blinding_reps.is_empty());
+            for rep in blinding_reps {
+                assert_ne!(
+                    prev_artifacts.generated_witness.values[rep],
+                    artifacts.generated_witness.values[rep]
+                );
+            }
+        }
+        if !data.common.config.zero_knowledge {
+            // Patching the previous commitment gives the same result as committing from scratch.
+            let config = &data.common.config;
+            let wires_commitment = PolynomialBatch::<F, C, D>::from_values(
+                wire_polynomials(&artifacts.witness, &mut TimingTree::default()),
+                config.fri_config.rate_bits,
+                false,
+                config.fri_config.cap_height,
+                &mut TimingTree::default(),
+                None,
+            );
+            assert_eq!(artifacts.wires_commitment, wires_commitment);
+        }
+        data.verify(proof)?;
+
+        // Chain another update on top of the updated artifacts.
+        let mut delta = PartialWitness::new();
+        delta.set_target(x, F::from_canonical_u32(11))?;
+        let (proof, artifacts) = prove_incremental(
+            &data.prover_only,
+            &data.common,
+            &artifacts,
+            delta,
+            &mut TimingTree::default(),
+        )?;
+        let expected = data.prove(inputs(11, 7))?;
+        assert_eq!(proof.public_inputs, expected.public_inputs);
+        assert_eq!(
+            artifacts.inputs().target_values,
+            inputs(11, 7).target_values
+        );
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_prove_incremental() -> Result<()> {
+        test_prove_incremental_with_config(CircuitConfig::standard_recursion_config())
+    }
+
+    #[test]
+    fn test_prove_incremental_zk() -> Result<()> {
+        test_prove_incremental_with_config(CircuitConfig::standard_recursion_zk_config())
+    }
+
+    #[test]
+    fn test_prove_incremental_rejects_generated_targets() -> Result<()> {
+        let (data, x, y) = circuit(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u32(3))?;
+        pw.set_target(y, F::from_canonical_u32(5))?;
+        let (_, artifacts) = prove_with_artifacts(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mut TimingTree::default(),
+        )?;
+
+        // The public inputs are computed by the circuit.
+        let mut delta = PartialWitness::new();
+        delta.set_target(data.prover_only.public_inputs[0], F::ONE)?;
+        assert!(prove_incremental(
+            &data.prover_only,
+            &data.common,
+            &artifacts,
+            delta,
+            &mut TimingTree::default(),
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+//! Detection of under-constrained targets in a built circuit.
+//!
+//! [`find_under_constrained`] generates a witness for the circuit, and reports:
+//! - the targets which are never involved in any constraint, i.e. virtual targets which are not
+//!   copy constrained to any wire, directly or transitively;
+//! - the sets of copy constrained targets whose common value can be altered without violating any
+//!   gate constraint. These are found by perturbing each set of targets by random values, keeping
+//!   the copy constraints satisfied, and evaluating the gate constraints on the affected rows.
+//!
+//! Both usually point at bugs in a circuit, such as a witness generator hinting a value which is
+//! never checked. The perturbation check is randomized, so it can miss values that are only
+//! partially constrained, e.g. a value free among a few others.
+//!
+//! **Note**: only gate constraints are evaluated. Lookup arguments are not checked, so the outputs
+//! of lookups may be reported as free.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::generate_partial_witness_recording_outputs;
+use crate::iop::target::Target;
+use crate::iop::witness::PartialWitness;
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::prover::complete_witness;
+use crate::plonk::vanishing_poly::evaluate_gate_constraints;
+use crate::plonk::vars::EvaluationVars;
+use crate::util::timing::TimingTree;
+
+/// The under-constrained targets of a circuit, as output by [`find_under_constrained`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UnderConstrainedReport {
+    /// The virtual targets which are not copy constrained to any wire, in increasing order.
+    pub unconstrained_targets: Vec<Target>,
+    /// The sets of copy constrained targets whose common value can be altered without violating
+    /// any gate constraint, with wires before virtual targets.
+    pub free_partitions: Vec<Vec<Target>>,
+}
+
+impl UnderConstrainedReport {
+    /// Returns whether no under-constrained target was found.
+    pub fn is_empty(&self) -> bool {
+        self.unconstrained_targets.is_empty() && self.free_partitions.is_empty()
+    }
+}
+
+/// Generates a witness for the circuit from `inputs`, and looks for under-constrained targets.
+///
+/// Each set of copy constrained targets whose value is set in the witness, other than by the
+/// generators of blinding values, and which contains at least one wire, is perturbed `num_trials`
+/// times; it is reported as free if the gate constraints hold in all trials. This evaluates the
+/// gate constraints of the affected rows once per trial, so it is only meant for circuits of
+/// moderate size.
+///
+/// Fails if witness generation fails, if the witness doesn't satisfy the gate constraints, or if
+/// the circuit data doesn't hold the constant polynomials, as for circuits built without
+/// committing to them.
+pub fn find_under_constrained<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    num_trials: usize,
+) -> Result<UnderConstrainedReport> {
+    let num_wires = common_data.config.num_wires;
+    let degree = common_data.degree();
+    let num_wire_targets = degree * num_wires;
+    let to_target = |i: usize| {
+        if i < num_wire_targets {
+            Target::wire(i / num_wires, i % num_wires)
+        } else {
+            Target::VirtualTarget {
+                index: i - num_wire_targets,
+            }
+        }
+    };
+
+    let constant_polys = &prover_data.constants_sigmas_commitment.polynomials;
+    ensure!(
+        constant_polys.len() >= common_data.num_constants,
+        "The circuit data doesn't hold the constant polynomials"
+    );
+    let constants = constant_polys[..common_data.num_constants]
+        .iter()
+        .map(|poly| poly.clone().fft().values)
+        .collect::<Vec<_>>();
+
+    let (partition_witness, generator_outputs) =
+        generate_partial_witness_recording_outputs(inputs, prover_data, common_data)?;
+    let is_set = partition_witness
+        .values
+        .iter()
+        .map(Option::is_some)
+        .collect::<Vec<_>>();
+    // Random values blinding the witness are free by design.
+    let mut is_random = vec![false; is_set.len()];
+    for (generator, outputs) in prover_data.generators.iter().zip(&generator_outputs) {
+        if generator.is_random_value() {
+            outputs.iter().for_each(|&i| is_random[i] = true);
+        }
+    }
+    let (public_inputs, witness) = complete_witness(
+        prover_data,
+        common_data,
+        partition_witness,
+        &mut TimingTree::default(),
+    )?;
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
+
+    // Evaluates the gate constraints of a row, with the given offsets added to some of its wires.
+    let row_satisfied = |row: usize, offsets: &[(usize, F)]| {
+        let mut local_wires = (0..num_wires)
+            .map(|column| witness.get_wire(row, column))
+            .collect::<Vec<_>>();
+        for &(column, offset) in offsets {
+            local_wires[column] += offset;
+        }
+        let local_wires = local_wires
+            .into_iter()
+            .map(F::Extension::from_basefield)
+            .collect::<Vec<_>>();
+        let local_constants = constants
+            .iter()
+            .map(|values| F::Extension::from_basefield(values[row]))
+            .collect::<Vec<_>>();
+        let vars = EvaluationVars {
+            local_constants: &local_constants,
+            local_wires: &local_wires,
+            public_inputs_hash: &public_inputs_hash,
+        };
+        evaluate_gate_constraints::<F, D>(common_data, vars)
+            .iter()
+            .all(|c| c.is_zero())
+    };
+
+    for row in 0..degree {
+        ensure!(
+            row_satisfied(row, &[]),
+            "The witness doesn't satisfy the gate constraints of row {}",
+            row
+        );
+    }
+
+    let mut partitions = HashMap::<usize, Vec<usize>>::new();
+    for (i, &representative) in prover_data.representative_map.iter().enumerate() {
+        partitions.entry(representative).or_default().push(i);
+    }
+    let mut partitions = partitions.into_iter().collect::<Vec<_>>();
+    partitions.sort_unstable_by_key(|(_, members)| members[0]);
+
+    let mut report = UnderConstrainedReport::default();
+    for (representative, members) in partitions {
+        if members[0] >= num_wire_targets {
+            report
+                .unconstrained_targets
+                .extend(members.into_iter().map(to_target));
+            continue;
+        }
+        if !is_set[representative] || is_random[representative] {
+            continue;
+        }
+
+        let mut wires_by_row = HashMap::<usize, Vec<usize>>::new();
+        for &i in members.iter().take_while(|&&i| i < num_wire_targets) {
+            wires_by_row
+                .entry(i / num_wires)
+                .or_default()
+                .push(i % num_wires);
+        }
+        let is_free = (0..num_trials).all(|_| {
+            let offset = F::rand();
+            wires_by_row.iter().all(|(&row, columns)| {
+                let offsets = columns.iter().map(|&c| (c, offset)).collect::<Vec<_>>();
+                row_satisfied(row, &offsets)
+            })
+        });
+        if is_free {
+            report
+                .free_partitions
+                .push(members.into_iter().map(to_target).collect());
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_sound_circuit() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::from_canonical_u32(3))?;
+        inputs.set_target(y, F::from_canonical_u32(5))?;
+        let report = data.find_under_constrained(inputs, 3)?;
+        assert!(report.is_empty(), "{:?}", report);
+        Ok(())
+    }
+
+    #[test]
+    fn test_under_constrained_circuit() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let [a, b, c, unused] = [(); 4].map(|_| builder.add_virtual_target());
+
+        // `0 * a * b + 1 * c`, which leaves `a` and `b` free.
+        let gate = ArithmeticGate::new_from_config(&config);
+        let row = builder.add_gate(gate.clone(), vec![F::ZERO, F::ONE]);
+        builder.connect(
+            a,
+            Target::wire(row, ArithmeticGate::wire_ith_multiplicand_0(0)),
+        );
+        builder.connect(
+            b,
+            Target::wire(row, ArithmeticGate::wire_ith_multiplicand_1(0)),
+        );
+        builder.connect(c, Target::wire(row, ArithmeticGate::wire_ith_addend(0)));
+        builder.register_public_input(Target::wire(row, ArithmeticGate::wire_ith_output(0)));
+        let zero = builder.zero();
+        for i in 1..gate.num_ops {
+            builder.connect(
+                zero,
+                Target::wire(row, ArithmeticGate::wire_ith_multiplicand_0(i)),
+            );
+            builder.connect(
+                zero,
+                Target::wire(row, ArithmeticGate::wire_ith_multiplicand_1(i)),
+            );
+            builder.connect(zero, Target::wire(row, ArithmeticGate::wire_ith_addend(i)));
+        }
+        let data = builder.build::<C>();
+
+        let mut inputs = PartialWitness::new();
+        for (target, value) in [(a, 2), (b, 3), (c, 4), (unused, 5)] {
+            inputs.set_target(target, F::from_canonical_u32(value))?;
+        }
+        let report = data.find_under_constrained(inputs, 3)?;
+        assert_eq!(report.unconstrained_targets, vec![unused]);
+        assert_eq!(
+            report.free_partitions,
+            vec![
+                vec![
+                    Target::wire(row, ArithmeticGate::wire_ith_multiplicand_0(0)),
+                    a
+                ],
+                vec![
+                    Target::wire(row, ArithmeticGate::wire_ith_multiplicand_1(0)),
+                    b
+                ],
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,407 @@
+//! Export of the data needed to implement the verifier of a circuit in another proving stack,
+//! such as gnark.
+//!
+//! [`export_verifier`] outputs a [`VerifierExport`], holding the Poseidon parameters, the FRI
+//! parameters, the shape of the circuit and its verifier-only data. [`verifier_test_vectors`]
+//! outputs [`VerifierTestVectors`] for a proof of that circuit: Poseidon permutations, the public
+//! inputs hash and all Fiat-Shamir challenges, against which a reimplementation can be checked
+//! step by step. Both can be serialized with any `serde` backend, typically JSON. Field elements
+//! are given by their canonical `u64` representation, and elements of the extension field as the
+//! list of their coefficients over the base field.
+//!
+//! The circuit digest, which seeds the Fiat-Shamir transcript, is
+//! `hash_no_pad(constants_sigmas_cap || hash_pad(domain_separator) || [degree_bits])`, where the
+//! Merkle cap is flattened hash by hash. The domain separator is not part of the circuit data, so
+//! [`VerifierOnlyExport::circuit_digest`] should be used as is.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::PrimeField64;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hashing::hash_n_to_hash_no_pad;
+use crate::hash::poseidon::{
+    PoseidonHash, PoseidonPermutation, ALL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS,
+    N_ROUNDS, SPONGE_CAPACITY, SPONGE_RATE, SPONGE_WIDTH,
+};
+use crate::plonk::circuit_data::VerifierCircuitData;
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+
+/// The parameters of the Poseidon permutation used by the verifier.
+///
+/// The MDS matrix is the sum of the circulant matrix whose first row is `mds_matrix_circ` and of
+/// the diagonal matrix whose diagonal is `mds_matrix_diag`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PoseidonParameters {
+    /// The width of the permutation.
+    pub width: usize,
+    /// The rate of the sponge.
+    pub rate: usize,
+    /// The capacity of the sponge.
+    pub capacity: usize,
+    /// The exponent of the S-box.
+    pub sbox_degree: u64,
+    /// The total number of full rounds, half of which are applied before the partial rounds.
+    pub full_rounds: usize,
+    /// The number of partial rounds.
+    pub partial_rounds: usize,
+    /// The constants added to the state at the start of each round.
+    pub round_constants: Vec<Vec<u64>>,
+    /// The first row of the circulant part of the MDS matrix.
+    pub mds_matrix_circ: Vec<u64>,
+    /// The diagonal part of the MDS matrix.
+    pub mds_matrix_diag: Vec<u64>,
+}
+
+/// The FRI parameters of a circuit.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FriExport {
+    /// `log2` of the inverse of the rate of the codes.
+    pub rate_bits: usize,
+    /// Height of the Merkle caps.
+    pub cap_height: usize,
+    /// Number of proof of work bits.
+    pub proof_of_work_bits: u32,
+    /// Number of query rounds.
+    pub num_query_rounds: usize,
+    /// `log2` of the arity of each reduction step.
+    pub reduction_arity_bits: Vec<usize>,
+    /// Whether the polynomials are blinded.
+    pub hiding: bool,
+}
+
+/// The shape of a circuit, which determines the layout of its proofs and openings.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CircuitExport {
+    /// `log2` of the number of rows.
+    pub degree_bits: usize,
+    /// The number of wires.
+    pub num_wires: usize,
+    /// The number of routed wires.
+    pub num_routed_wires: usize,
+    /// The number of constant columns, including selectors.
+    pub num_constants: usize,
+    /// The number of public inputs.
+    pub num_public_inputs: usize,
+    /// The number of challenges of the permutation argument.
+    pub num_challenges: usize,
+    /// The degree of the quotient polynomial, divided by the number of rows.
+    pub quotient_degree_factor: usize,
+    /// The number of partial products of the permutation argument.
+    pub num_partial_products: usize,
+    /// The number of constraints of the gates.
+    pub num_gate_constraints: usize,
+    /// The cosets shifts of the permutation argument.
+    pub k_is: Vec<u64>,
+    /// The identifiers of the gates, in order.
+    pub gates: Vec<String>,
+    /// The index of the selector of each gate.
+    pub selector_indices: Vec<usize>,
+    /// The range of gates handled by each selector.
+    pub selector_groups: Vec<(usize, usize)>,
+    /// The number of lookup polynomials.
+    pub num_lookup_polys: usize,
+    /// The number of lookup selectors.
+    pub num_lookup_selectors: usize,
+}
+
+/// The verifier-only data of a circuit.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VerifierOnlyExport {
+    /// The Merkle cap of the constant and sigma polynomials.
+    pub constants_sigmas_cap: Vec<Vec<u64>>,
+    /// The digest of the circuit.
+    pub circuit_digest: Vec<u64>,
+}
+
+/// Everything needed to implement the verifier of a circuit, as output by [`export_verifier`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct VerifierExport {
+    /// The order of the base field.
+    pub field_order: u64,
+    /// The degree of the extension field.
+    pub extension_degree: usize,
+    /// The constant `W` such that the extension field is `F[X] / (X^D - W)`.
+    pub extension_w: u64,
+    /// The parameters of the Poseidon permutation.
+    pub poseidon: PoseidonParameters,
+    /// The FRI parameters.
+    pub fri: FriExport,
+    /// The shape of the circuit.
+    pub circuit: CircuitExport,
+    /// The verifier-only data of the circuit.
+    pub verifier_only: VerifierOnlyExport,
+}
+
+/// An input and output of the Poseidon permutation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PoseidonTestVector {
+    /// The input state.
+    pub input: Vec<u64>,
+    /// The output state.
+    pub output: Vec<u64>,
+}
+
+/// The Fiat-Shamir challenges of a proof.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ChallengesExport {
+    /// Random values used in Plonk's permutation argument.
+    pub plonk_betas: Vec<u64>,
+    /// Random values used in Plonk's permutation argument.
+    pub plonk_gammas: Vec<u64>,
+    /// Random values used to combine PLONK constraints.
+    pub plonk_alphas: Vec<u64>,
+    /// Lookup challenges.
+    pub plonk_deltas: Vec<u64>,
+    /// Point at which the PLONK polynomials are opened.
+    pub plonk_zeta: Vec<u64>,
+    /// Scaling factor to combine polynomials in FRI.
+    pub fri_alpha: Vec<u64>,
+    /// Betas used in the FRI commit phase reductions.
+    pub fri_betas: Vec<Vec<u64>>,
+    /// The proof of work response.
+    pub fri_pow_response: u64,
+    /// Indices at which the FRI oracle is queried.
+    pub fri_query_indices: Vec<usize>,
+}
+
+/// Reference values for a proof, as output by [`verifier_test_vectors`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(bound = "")]
+pub struct VerifierTestVectors<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// Inputs and outputs of the Poseidon permutation.
+    pub poseidon: Vec<PoseidonTestVector>,
+    /// The proof the following values are derived from.
+    pub proof: ProofWithPublicInputs<F, C, D>,
+    /// The hash of the public inputs of the proof.
+    pub public_inputs_hash: Vec<u64>,
+    /// The challenges of the proof.
+    pub challenges: ChallengesExport,
+}
+
+fn canonical<F: PrimeField64>(values: &[F]) -> Vec<u64> {
+    values.iter().map(|x| x.to_canonical_u64()).collect()
+}
+
+fn canonical_ext<F: RichField + Extendable<D>, const D: usize>(value: F::Extension) -> Vec<u64> {
+    canonical(&value.to_basefield_array())
+}
+
+/// Exports the data needed to implement the verifier of the given circuit.
+pub fn export_verifier<F, C, const D: usize>(data: &VerifierCircuitData<F, C, D>) -> VerifierExport
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F, Hasher = PoseidonHash, InnerHasher = PoseidonHash>,
+{
+    let common = &data.common;
+    let config = &common.config;
+    let fri_params = &common.fri_params;
+
+    let poseidon = PoseidonParameters {
+        width: SPONGE_WIDTH,
+        rate: SPONGE_RATE,
+        capacity: SPONGE_CAPACITY,
+        sbox_degree: 7,
+        full_rounds: 2 * HALF_N_FULL_ROUNDS,
+        partial_rounds: N_PARTIAL_ROUNDS,
+        round_constants: ALL_ROUND_CONSTANTS[..SPONGE_WIDTH * N_ROUNDS]
+            .chunks(SPONGE_WIDTH)
+            .map(|round| round.to_vec())
+            .collect(),
+        mds_matrix_circ: F::MDS_MATRIX_CIRC.to_vec(),
+        mds_matrix_diag: F::MDS_MATRIX_DIAG.to_vec(),
+    };
+
+    let fri = FriExport {
+        rate_bits: fri_params.config.rate_bits,
+        cap_height: fri_params.config.cap_height,
+        proof_of_work_bits: fri_params.config.proof_of_work_bits,
+        num_query_rounds: fri_params.config.num_query_rounds,
+        reduction_arity_bits: fri_params.reduction_arity_bits.clone(),
+        hiding: fri_params.hiding,
+    };
+
+    let circuit = CircuitExport {
+        degree_bits: common.degree_bits(),
+        num_wires: config.num_wires,
+        num_routed_wires: config.num_routed_wires,
+        num_constants: common.num_constants,
+        num_public_inputs: common.num_public_inputs,
+        num_challenges: config.num_challenges,
+        quotient_degree_factor: common.quotient_degree_factor,
+        num_partial_products: common.num_partial_products,
+        num_gate_constraints: common.num_gate_constraints,
+        k_is: canonical(&common.k_is),
+        gates: common.gates.iter().map(|g| g.0.id()).collect(),
+        selector_indices: common.selectors_info.selector_indices.clone(),
+        selector_groups: common
+            .selectors_info
+            .groups
+            .iter()
+            .map(|r| (r.start, r.end))
+            .collect(),
+        num_lookup_polys: common.num_lookup_polys,
+        num_lookup_selectors: common.num_lookup_selectors,
+    };
+
+    let verifier_only = VerifierOnlyExport {
+        constants_sigmas_cap: data
+            .verifier_only
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .map(|h| canonical(&h.elements))
+            .collect(),
+        circuit_digest: canonical(&data.verifier_only.circuit_digest.elements),
+    };
+
+    VerifierExport {
+        field_order: F::ORDER,
+        extension_degree: D,
+        extension_w: F::W.to_canonical_u64(),
+        poseidon,
+        fri,
+        circuit,
+        verifier_only,
+    }
+}
+
+/// Computes reference values for the given proof, after checking that it is valid.
+pub fn verifier_test_vectors<F, C, const D: usize>(
+    data: &VerifierCircuitData<F, C, D>,
+    proof: &ProofWithPublicInputs<F, C, D>,
+) -> Result<VerifierTestVectors<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F, Hasher = PoseidonHash, InnerHasher = PoseidonHash>,
+{
+    data.verify(proof.clone())?;
+
+    let poseidon = [
+        [F::ZERO; SPONGE_WIDTH],
+        core::array::from_fn(F::from_canonical_usize),
+        [F::NEG_ONE; SPONGE_WIDTH],
+    ]
+    .iter()
+    .map(|&input| PoseidonTestVector {
+        input: canonical(&input),
+        output: canonical(&F::poseidon(input)),
+    })
+    .collect();
+
+    let public_inputs_hash: HashOut<F> =
+        hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&proof.public_inputs);
+    let challenges = proof.get_challenges(
+        public_inputs_hash,
+        &data.verifier_only.circuit_digest,
+        &data.common,
+    )?;
+    let fri_challenges = &challenges.fri_challenges;
+
+    Ok(VerifierTestVectors {
+        poseidon,
+        proof: proof.clone(),
+        public_inputs_hash: canonical(&public_inputs_hash.elements),
+        challenges: ChallengesExport {
+            plonk_betas: canonical(&challenges.plonk_betas),
+            plonk_gammas: canonical(&challenges.plonk_gammas),
+            plonk_alphas: canonical(&challenges.plonk_alphas),
+            plonk_deltas: canonical(&challenges.plonk_deltas),
+            plonk_zeta: canonical_ext::<F, D>(challenges.plonk_zeta),
+            fri_alpha: canonical_ext::<F, D>(fri_challenges.fri_alpha),
+            fri_betas: fri_challenges
+                .fri_betas
+                .iter()
+                .map(|&beta| canonical_ext::<F, D>(beta))
+                .collect(),
+            fri_pow_response: fri_challenges.fri_pow_response.to_canonical_u64(),
+            fri_query_indices: fri_challenges.fri_query_indices.clone(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::hash::hashing::hash_n_to_m_no_pad;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{Hasher, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_verifier_export() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u32(3))?;
+        pw.set_target(y, F::from_canonical_u32(7))?;
+        let proof = data.prove(pw)?;
+        let verifier_data = data.verifier_data();
+
+        let export = export_verifier(&verifier_data);
+        assert_eq!(export.poseidon.round_constants.len(), N_ROUNDS);
+        assert_eq!(export.circuit.gates.len(), data.common.gates.len());
+        assert_eq!(
+            export.fri.num_query_rounds,
+            data.common.config.fri_config.num_query_rounds
+        );
+
+        // The circuit digest follows the documented layout, with the default domain separator.
+        let domain_separator_digest = PoseidonHash::hash_pad(&[]);
+        let digest_input = [
+            data.verifier_only.constants_sigmas_cap.flatten(),
+            domain_separator_digest.elements.to_vec(),
+            vec![F::from_canonical_usize(export.circuit.degree_bits)],
+        ]
+        .concat();
+        assert_eq!(
+            canonical(&PoseidonHash::hash_no_pad(&digest_input).elements),
+            export.verifier_only.circuit_digest
+        );
+
+        let vectors = verifier_test_vectors(&verifier_data, &proof)?;
+        assert_eq!(
+            vectors.public_inputs_hash,
+            canonical(&hash_n_to_m_no_pad::<F, PoseidonPermutation<F>>(
+                &[F::from_canonical_u32(21)],
+                4
+            ))
+        );
+        assert_eq!(
+            vectors.challenges.fri_query_indices.len(),
+            export.fri.num_query_rounds
+        );
+        assert_eq!(
+            vectors.challenges.fri_betas.len(),
+            export.fri.reduction_arity_bits.len()
+        );
+
+        // Both outputs are meant to be serialized.
+        serde_json::to_string(&export)?;
+        serde_json::to_string(&vectors)?;
+        Ok(())
+    }
+}
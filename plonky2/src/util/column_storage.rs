@@ -0,0 +1,19 @@
+//! Selection of where the large columns of the prover, i.e. the LDEs of the committed polynomials
+//! and the leaves of their Merkle trees, are stored.
+
+#[cfg(all(feature = "mmap", unix))]
+use std::path::PathBuf;
+
+/// Where the columns of a computation are stored.
+///
+/// Only [`ColumnStorage::Memory`] is available without the `mmap` feature. See
+/// [`mmap`](crate::util::mmap) for the other storages.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ColumnStorage {
+    /// Columns are regular vectors in memory.
+    #[default]
+    Memory,
+    /// Columns are memory-mapped temporary files in the given directory.
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(PathBuf),
+}
@@ -0,0 +1,228 @@
+//! Opt-in tracking of memory usage, reported per scope through a
+//! [`TimingTree`](crate::util::timing::TimingTree).
+//!
+//! Allocations are only accounted for when [`TrackingAllocator`] is installed as the global
+//! allocator of the binary:
+//!
+//! ```
+//! use std::alloc::System;
+//!
+//! use log::Level;
+//! use plonky2::timed;
+//! use plonky2::util::memory::TrackingAllocator;
+//! use plonky2::util::timing::TimingTree;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: TrackingAllocator = TrackingAllocator(System);
+//!
+//! fn main() {
+//!     let mut timing = TimingTree::new_tracking_memory("prove", Level::Debug);
+//!     let lde = timed!(timing, "compute LDE", vec![0u64; 1 << 20]);
+//!     timed!(timing, "build Merkle tree", drop(vec![0u64; 1 << 21]));
+//!     timing.pop();
+//!
+//!     let spans = timing.spans();
+//!     let lde_usage = spans.children[0].memory.unwrap();
+//!     assert!(lde_usage.allocated_on_exit >= lde_usage.allocated_on_enter + (8 << 20));
+//!     let merkle_usage = spans.children[1].memory.unwrap();
+//!     assert!(merkle_usage.peak_allocated >= merkle_usage.allocated_on_enter + (16 << 20));
+//!     // The root scope accounts for the peaks of its children.
+//!     assert!(spans.memory.unwrap().peak_allocated >= merkle_usage.peak_allocated);
+//!     drop(lde);
+//! }
+//! ```
+//!
+//! Scopes of a tree created with
+//! [`TimingTree::new_tracking_memory`](crate::util::timing::TimingTree::new_tracking_memory) then
+//! record the bytes allocated when they are entered and exited, and the peak number of bytes
+//! allocated while they are open. On Linux, the peak resident set size of the process is also
+//! sampled when each scope is exited.
+//!
+//! Each open scope keeps its own running peak, so that trees used concurrently, e.g. on different
+//! threads, don't interfere with each other. Allocations are however counted process-wide, so the
+//! peak of a scope includes the allocations made by other threads while it is open. At most
+//! [`MAX_PEAK_SCOPES`] scopes can track their peak at the same time; the peaks of the scopes opened
+//! beyond that are approximated by the larger of their allocations on enter and on exit.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::alloc::System;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The maximum number of memory-tracking scopes whose peak can be tracked at the same time, across
+/// all timing trees.
+pub const MAX_PEAK_SCOPES: usize = 64;
+
+// Each open scope owns a slot holding its running peak. A slot is claimed, then initialized, then
+// activated, after which allocations update it until it is released.
+static CLAIMED_SLOTS: AtomicU64 = AtomicU64::new(0);
+static ACTIVE_SLOTS: AtomicU64 = AtomicU64::new(0);
+#[allow(clippy::declare_interior_mutable_const)]
+const NO_PEAK: AtomicUsize = AtomicUsize::new(0);
+static SCOPE_PEAKS: [AtomicUsize; MAX_PEAK_SCOPES] = [NO_PEAK; MAX_PEAK_SCOPES];
+
+/// A global allocator wrapping another one, and counting the bytes currently allocated through
+/// it.
+#[derive(Debug, Default)]
+pub struct TrackingAllocator<A = System>(pub A);
+
+fn record_alloc(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+    let mut active = ACTIVE_SLOTS.load(Ordering::Acquire);
+    while active != 0 {
+        SCOPE_PEAKS[active.trailing_zeros() as usize].fetch_max(allocated, Ordering::Relaxed);
+        active &= active - 1;
+    }
+}
+
+fn record_dealloc(size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.0.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// The number of bytes currently allocated through [`TrackingAllocator`].
+pub fn allocated_bytes() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// The peak number of bytes allocated through [`TrackingAllocator`] since the start of the
+/// process.
+pub fn peak_allocated_bytes() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// The running peak of the bytes allocated since a scope was entered. It stops being updated once
+/// dropped.
+#[derive(Debug)]
+pub(crate) struct PeakScope {
+    /// The slot of the scope, or `None` if [`MAX_PEAK_SCOPES`] scopes were already open.
+    slot: Option<usize>,
+    allocated_on_enter: usize,
+}
+
+impl PeakScope {
+    /// Starts measuring the peak of a new scope.
+    pub(crate) fn enter() -> Self {
+        let allocated_on_enter = allocated_bytes();
+        let slot = CLAIMED_SLOTS
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |claimed| {
+                (claimed != u64::MAX).then_some(claimed | (claimed + 1))
+            })
+            .ok()
+            .map(|claimed| claimed.trailing_ones() as usize);
+        if let Some(slot) = slot {
+            SCOPE_PEAKS[slot].store(allocated_on_enter, Ordering::Relaxed);
+            ACTIVE_SLOTS.fetch_or(1 << slot, Ordering::Release);
+        }
+        Self {
+            slot,
+            allocated_on_enter,
+        }
+    }
+
+    /// The peak number of bytes allocated since the scope was entered.
+    pub(crate) fn peak(&self) -> usize {
+        let allocated = allocated_bytes();
+        match self.slot {
+            Some(slot) => SCOPE_PEAKS[slot].load(Ordering::Relaxed).max(allocated),
+            None => self.allocated_on_enter.max(allocated),
+        }
+    }
+}
+
+impl Drop for PeakScope {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            ACTIVE_SLOTS.fetch_and(!(1 << slot), Ordering::Relaxed);
+            CLAIMED_SLOTS.fetch_and(!(1 << slot), Ordering::Release);
+        }
+    }
+}
+
+/// The peak resident set size of the process in bytes, if it can be sampled on this platform.
+pub fn peak_rss_bytes() -> Option<usize> {
+    proc_status_kib("VmHWM:").map(|kib| kib * 1024)
+}
+
+/// The current resident set size of the process in bytes, if it can be sampled on this
+/// platform.
+pub fn rss_bytes() -> Option<usize> {
+    proc_status_kib("VmRSS:").map(|kib| kib * 1024)
+}
+
+#[cfg(target_os = "linux")]
+fn proc_status_kib(key: &str) -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(key))?;
+    line[key.len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn proc_status_kib(_key: &str) -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleaved_peak_scopes() {
+        // The tracking allocator isn't installed in tests, so allocations are recorded by hand.
+        let base = allocated_bytes();
+        let first = PeakScope::enter();
+        record_alloc(1000);
+        record_dealloc(1000);
+        let second = PeakScope::enter();
+        record_alloc(10);
+        assert_eq!(first.peak(), base + 1000);
+        assert_eq!(second.peak(), base + 10);
+
+        // Scopes don't need to be exited in reverse order, as with trees on different threads.
+        drop(first);
+        record_alloc(2000);
+        assert_eq!(second.peak(), base + 2010);
+        record_dealloc(2010);
+        let third = PeakScope::enter();
+        assert_eq!(third.peak(), base);
+    }
+}
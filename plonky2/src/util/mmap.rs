@@ -0,0 +1,364 @@
+//! Columns of field elements backed by memory-mapped files, for traces whose LDEs are larger than
+//! the available RAM.
+//!
+//! A [`MmapVec`] is a fixed-length buffer living in an unlinked temporary file, which the OS pages
+//! in and out as needed. It dereferences to a slice, so that in-place algorithms such as
+//! [`fft_in_place`] run on it unchanged; processing it sequentially, one column or one chunk of
+//! rows at a time, keeps the resident memory bounded and the disk accesses mostly contiguous.
+//!
+//! [`ColumnStorage::Mapped`] selects this storage.
+//! [`PolynomialBatch::from_coeffs_with_storage`](crate::fri::oracle::PolynomialBatch::from_coeffs_with_storage)
+//! then computes the LDEs of a batch in mapped columns, and transposes them into mapped Merkle
+//! leaves one chunk of rows at a time, hashing each leaf as it is written. The resulting
+//! [`PolynomialBatch`] keeps its leaves mapped, so that only its coefficients, which are as large
+//! as the trace itself, and the digests of its Merkle tree stay in memory.
+//! [`prove_with_storage`](crate::plonk::prover::prove_with_storage) commits to the wires, the `Z`s
+//! and the quotient this way, and starky's `prove_with_storage` commits to the trace. The
+//! quotient polynomials are still evaluated in memory, over an extended domain of
+//! `quotient_degree_factor` times the size of the trace.
+//!
+//! [`PolynomialBatch`]: crate::fri::oracle::PolynomialBatch
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use plonky2_maybe_rayon::*;
+
+use crate::field::fft::{fft_in_place, ifft_in_place, FftRootTable};
+use crate::field::polynomial::PolynomialValues;
+use crate::field::types::Field;
+pub use crate::util::column_storage::ColumnStorage;
+use crate::util::POWERS_CHUNK_SIZE;
+
+/// A fixed-length buffer of field elements backed by a memory-mapped temporary file.
+#[derive(Debug)]
+pub struct MmapVec<F: Field> {
+    ptr: NonNull<F>,
+    len: usize,
+    // Keeps the file open for as long as it is mapped.
+    file: Option<File>,
+    _marker: PhantomData<F>,
+}
+
+// The mapping is owned exclusively by the `MmapVec`, like the allocation of a `Vec`.
+unsafe impl<F: Field> Send for MmapVec<F> {}
+unsafe impl<F: Field> Sync for MmapVec<F> {}
+
+impl<F: Field> MmapVec<F> {
+    /// Creates a buffer of `len` zeros, in a new temporary file in `dir`. The file is unlinked
+    /// immediately, so it is removed once the buffer is dropped, even if the process crashes.
+    pub fn zeroed(len: usize, dir: &Path) -> io::Result<Self> {
+        let num_bytes = len * core::mem::size_of::<F>();
+        if num_bytes == 0 {
+            return Ok(Self {
+                ptr: NonNull::dangling(),
+                len,
+                file: None,
+                _marker: PhantomData,
+            });
+        }
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = dir.join(format!(
+            "plonky2_mmap_{}_{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        std::fs::remove_file(&path)?;
+        // Extending the file fills it with zeros, which is a valid representation of `F::ZERO` for
+        // all our fields.
+        file.set_len(num_bytes as u64)?;
+
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                num_bytes,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            ptr: NonNull::new(ptr.cast()).expect("mmap returned a null pointer"),
+            len,
+            file: Some(file),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Creates a buffer holding a copy of `values`, in a new temporary file in `dir`.
+    pub fn from_slice(values: &[F], dir: &Path) -> io::Result<Self> {
+        let mut buffer = Self::zeroed(values.len(), dir)?;
+        buffer.copy_from_slice(values);
+        Ok(buffer)
+    }
+}
+
+impl<F: Field> Deref for MmapVec<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<F: Field> DerefMut for MmapVec<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<F: Field> Drop for MmapVec<F> {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            unsafe {
+                libc::munmap(
+                    self.ptr.as_ptr().cast(),
+                    self.len * core::mem::size_of::<F>(),
+                );
+            }
+        }
+    }
+}
+
+/// A column of field elements, stored as per a [`ColumnStorage`].
+#[derive(Debug)]
+pub enum Column<F: Field> {
+    /// A column in memory.
+    Memory(Vec<F>),
+    /// A column in a memory-mapped file.
+    Mapped(MmapVec<F>),
+}
+
+impl<F: Field> Deref for Column<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        match self {
+            Self::Memory(values) => values,
+            Self::Mapped(values) => values,
+        }
+    }
+}
+
+impl<F: Field> DerefMut for Column<F> {
+    fn deref_mut(&mut self) -> &mut [F] {
+        match self {
+            Self::Memory(values) => values,
+            Self::Mapped(values) => values,
+        }
+    }
+}
+
+impl ColumnStorage {
+    /// Allocates a column of `len` zeros.
+    pub fn zeroed<F: Field>(&self, len: usize) -> io::Result<Column<F>> {
+        match self {
+            Self::Memory => Ok(Column::Memory(vec![F::ZERO; len])),
+            Self::Mapped(dir) => MmapVec::zeroed(len, dir).map(Column::Mapped),
+        }
+    }
+
+    /// Stores the values of `poly` in a column.
+    pub fn column<F: Field>(&self, poly: PolynomialValues<F>) -> io::Result<Column<F>> {
+        match self {
+            Self::Memory => Ok(Column::Memory(poly.values)),
+            Self::Mapped(dir) => MmapVec::from_slice(&poly.values, dir).map(Column::Mapped),
+        }
+    }
+
+    /// Computes the low-degree extension of `values` onto the coset shifted by
+    /// [`Field::coset_shift`], like
+    /// [`PolynomialValues::lde_onto_coset`], but in a single column of this storage, without
+    /// intermediate copies. `root_table` must be the root table of the extended domain, if any.
+    pub fn coset_lde<F: Field>(
+        &self,
+        values: &[F],
+        rate_bits: usize,
+        root_table: Option<&FftRootTable<F>>,
+    ) -> io::Result<Column<F>> {
+        let n = values.len();
+        let mut column = self.zeroed(n << rate_bits)?;
+        column[..n].copy_from_slice(values);
+        ifft_in_place(&mut column[..n], None, None);
+        coset_lde_in_place(&mut column, n, rate_bits, root_table);
+        Ok(column)
+    }
+
+    /// Like [`Self::coset_lde`], but from the coefficients of the polynomial rather than from its
+    /// values.
+    pub fn coset_lde_of_coeffs<F: Field>(
+        &self,
+        coeffs: &[F],
+        rate_bits: usize,
+        root_table: Option<&FftRootTable<F>>,
+    ) -> io::Result<Column<F>> {
+        let n = coeffs.len();
+        let mut column = self.zeroed(n << rate_bits)?;
+        column[..n].copy_from_slice(coeffs);
+        coset_lde_in_place(&mut column, n, rate_bits, root_table);
+        Ok(column)
+    }
+}
+
+/// Replaces the `n` coefficients at the start of `column`, which is zero afterwards, with the
+/// evaluations of their polynomial on the coset of the size of `column`.
+fn coset_lde_in_place<F: Field>(
+    column: &mut [F],
+    n: usize,
+    rate_bits: usize,
+    root_table: Option<&FftRootTable<F>>,
+) {
+    {
+        let coeffs = &mut column[..n];
+        let chunks = F::coset_shift()
+            .powers()
+            .chunks(POWERS_CHUNK_SIZE)
+            .take(n.div_ceil(POWERS_CHUNK_SIZE))
+            .collect::<Vec<_>>();
+        coeffs
+            .par_chunks_mut(POWERS_CHUNK_SIZE)
+            .zip(chunks)
+            .for_each(|(chunk, powers)| chunk.iter_mut().zip(powers).for_each(|(c, r)| *c *= r));
+    }
+    fft_in_place(column, Some(rate_bits), root_table);
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::fri::oracle::PolynomialBatch;
+    use crate::hash::merkle_proofs::verify_merkle_proof_to_cap;
+    use crate::hash::merkle_tree::MerkleLeaves;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::prover::prove_with_storage;
+    use crate::util::timing::TimingTree;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn mapped() -> ColumnStorage {
+        ColumnStorage::Mapped(std::env::temp_dir())
+    }
+
+    #[test]
+    fn test_mmap_vec() -> io::Result<()> {
+        let values = F::rand_vec(1000);
+        let mut buffer = MmapVec::from_slice(&values, &std::env::temp_dir())?;
+        assert_eq!(&buffer[..], &values[..]);
+        buffer[3] = F::ONE;
+        assert_eq!(buffer[3], F::ONE);
+        assert!(MmapVec::<F>::zeroed(0, &std::env::temp_dir())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_coset_lde() -> io::Result<()> {
+        let values = PolynomialValues::new(F::rand_vec(1 << 6));
+        let expected = values.clone().lde_onto_coset(3);
+        for storage in [ColumnStorage::Memory, mapped()] {
+            let column = storage.coset_lde(&values.values, 3, None)?;
+            assert_eq!(&column[..], &expected.values[..]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_polynomial_batch_with_storage() -> Result<()> {
+        let values: Vec<_> = (0..5)
+            .map(|_| PolynomialValues::new(F::rand_vec(1 << 5)))
+            .collect();
+        let from_values_with_storage = |values, blinding, chunk_size| {
+            PolynomialBatch::<F, C, D>::from_values_with_storage(
+                values,
+                2,
+                blinding,
+                1,
+                &mut TimingTree::default(),
+                None,
+                &mapped(),
+                chunk_size,
+            )
+        };
+        let expected = PolynomialBatch::<F, C, D>::from_values(
+            values.clone(),
+            2,
+            false,
+            1,
+            &mut TimingTree::default(),
+            None,
+        );
+        let batch = from_values_with_storage(values.clone(), false, 7)?;
+        assert!(matches!(
+            batch.merkle_tree.leaves,
+            MerkleLeaves::Mapped { .. }
+        ));
+        assert_eq!(batch, expected);
+
+        // With blinding, the leaves are salted, but the LDE values are unchanged.
+        let blinded_batch = from_values_with_storage(values, true, 7)?;
+        for i in 0..1 << 7 {
+            assert_eq!(
+                blinded_batch.get_lde_values(i, 1),
+                expected.get_lde_values(i, 1)
+            );
+            verify_merkle_proof_to_cap(
+                blinded_batch.merkle_tree.leaves[i].to_vec(),
+                i,
+                &blinded_batch.merkle_tree.cap,
+                &blinded_batch.merkle_tree.prove(i),
+            )?;
+        }
+
+        assert!(from_values_with_storage(vec![], false, 7).is_err());
+        assert!(from_values_with_storage(vec![PolynomialValues::zero(1 << 5)], false, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_with_storage() -> Result<()> {
+        let mut builder =
+            CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_zk_config());
+        let x = builder.add_virtual_target();
+        let mut y = x;
+        for _ in 0..100 {
+            y = builder.mul(y, x);
+        }
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO)?;
+        let proof = prove_with_storage(
+            &data.prover_only,
+            &data.common,
+            pw,
+            &mapped(),
+            &mut TimingTree::default(),
+        )?;
+        data.verify(proof)
+    }
+}
@@ -1,7 +1,7 @@
 //! Utility module for helper methods and plonky2 serialization logic.
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 use plonky2_maybe_rayon::*;
 #[doc(inline)]
@@ -10,11 +10,17 @@ pub use plonky2_util::*;
 use crate::field::polynomial::PolynomialValues;
 use crate::field::types::Field;
 
+pub mod column_storage;
 pub(crate) mod context_tree;
+#[cfg(feature = "std")]
+pub mod memory;
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
 pub(crate) mod partial_products;
 pub mod reducing;
 pub mod serialization;
 pub mod strided_view;
+pub mod symbolic;
 pub mod timing;
 
 pub(crate) fn transpose_poly_values<F: Field>(polys: Vec<PolynomialValues<F>>) -> Vec<Vec<F>> {
@@ -30,6 +36,30 @@ pub fn transpose<T: Send + Sync + Copy>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
         .collect()
 }
 
+/// The number of consecutive powers computed by each task of [`par_shifted_powers`].
+pub(crate) const POWERS_CHUNK_SIZE: usize = 1 << 12;
+
+/// Returns `start, start * base, ..., start * base^(n - 1)`, computed in parallel over chunks of
+/// powers.
+pub fn par_shifted_powers<F: Field>(base: F, start: F, n: usize) -> Vec<F> {
+    let mut powers = vec![F::ZERO; n];
+    let chunks = base
+        .shifted_powers(start)
+        .chunks(POWERS_CHUNK_SIZE)
+        .take(n.div_ceil(POWERS_CHUNK_SIZE))
+        .collect::<Vec<_>>();
+    powers
+        .par_chunks_mut(POWERS_CHUNK_SIZE)
+        .zip(chunks)
+        .for_each(|(chunk, chunk_powers)| {
+            chunk
+                .iter_mut()
+                .zip(chunk_powers)
+                .for_each(|(x, power)| *x = power)
+        });
+    powers
+}
+
 pub(crate) const fn reverse_bits(n: usize, num_bits: usize) -> usize {
     // NB: The only reason we need overflowing_shr() here as opposed
     // to plain '>>' is to accommodate the case n == num_bits == 0,
@@ -48,6 +78,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_par_shifted_powers() {
+        type F = crate::field::goldilocks_field::GoldilocksField;
+
+        let base = F::from_canonical_u64(5);
+        let start = F::from_canonical_u64(3);
+        for n in [0, 1, POWERS_CHUNK_SIZE - 1, 2 * POWERS_CHUNK_SIZE + 7] {
+            assert_eq!(
+                par_shifted_powers(base, start, n),
+                base.shifted_powers(start).take(n).collect::<Vec<_>>()
+            );
+        }
+    }
+
     #[test]
     fn test_reverse_bits() {
         assert_eq!(reverse_bits(0b0000000000, 10), 0b0000000000);
@@ -103,6 +103,7 @@ pub mod default {
     use crate::gates::exponentiation::ExponentiationGate;
     use crate::gates::lookup::LookupGate;
     use crate::gates::lookup_table::LookupTableGate;
+    use crate::gates::mul_add::MulAddGate;
     use crate::gates::multiplication_extension::MulExtensionGate;
     use crate::gates::noop::NoopGate;
     use crate::gates::poseidon::PoseidonGate;
@@ -142,7 +143,9 @@ pub mod default {
             PublicInputGate,
             RandomAccessGate<F, D>,
             ReducingExtensionGate<D>,
-            ReducingGate<D>
+            ReducingGate<D>,
+            // Tags are positions in this list: new gates go last to keep serialized data valid.
+            MulAddGate
         }
     }
 }
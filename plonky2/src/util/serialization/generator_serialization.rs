@@ -113,6 +113,7 @@ pub mod default {
     use crate::gates::exponentiation::ExponentiationGenerator;
     use crate::gates::lookup::LookupGenerator;
     use crate::gates::lookup_table::LookupTableGenerator;
+    use crate::gates::mul_add::MulAddGenerator;
     use crate::gates::multiplication_extension::MulExtensionGenerator;
     use crate::gates::poseidon::PoseidonGenerator;
     use crate::gates::poseidon_mds::PoseidonMdsGenerator;
@@ -176,7 +177,9 @@ pub mod default {
             ReducingGenerator<D>,
             ReducingExtensionGenerator<D>,
             SplitGenerator,
-            WireSplitGenerator
+            WireSplitGenerator,
+            // Tags are positions in this list: new generators go last to keep serialized data valid.
+            MulAddGenerator
         }
     }
 }
@@ -3,6 +3,7 @@ pub mod generator_serialization;
 
 #[macro_use]
 pub mod gate_serialization;
+pub mod versioned;
 
 #[cfg(not(feature = "std"))]
 use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
@@ -65,6 +66,14 @@ impl Display for IoError {
 /// A no_std compatible variant of `std::io::Result`
 pub type IoResult<T> = Result<T, IoError>;
 
+/// Allocates a vector for `len` items read from untrusted input. The preallocated capacity is
+/// bounded, so that a corrupted length fails when reading the items rather than aborting on a huge
+/// allocation.
+fn cautious_vec<T>(len: usize) -> Vec<T> {
+    const MAX_PREALLOCATED_BYTES: usize = 1 << 20;
+    Vec::with_capacity(len.min(MAX_PREALLOCATED_BYTES / size_of::<T>().max(1)))
+}
+
 /// A `Read` which is able to report how many bytes are remaining.
 pub trait Remaining: Read {
     /// Returns the number of bytes remaining in the buffer.
@@ -143,7 +152,7 @@ pub trait Read {
     #[inline]
     fn read_usize_vec(&mut self) -> IoResult<Vec<usize>> {
         let len = self.read_usize()?;
-        let mut res = Vec::with_capacity(len);
+        let mut res = cautious_vec(len);
         for _ in 0..len {
             res.push(self.read_usize()?);
         }
@@ -294,7 +303,10 @@ pub trait Read {
         F: RichField,
         H: Hasher<F>,
     {
-        let cap_length = 1 << cap_height;
+        let cap_length = u32::try_from(cap_height)
+            .ok()
+            .and_then(|h| 1usize.checked_shl(h))
+            .ok_or(IoError)?;
         Ok(MerkleCap(
             (0..cap_length)
                 .map(|_| self.read_hash::<F, H>())
@@ -321,7 +333,7 @@ pub trait Read {
         H: Hasher<F>,
     {
         let leaves_len = self.read_usize()?;
-        let mut leaves = Vec::with_capacity(leaves_len);
+        let mut leaves = cautious_vec(leaves_len);
         for _ in 0..leaves_len {
             let leaf_len = self.read_usize()?;
             leaves.push(self.read_field_vec(leaf_len)?);
@@ -332,7 +344,7 @@ pub trait Read {
         let cap_height = self.read_usize()?;
         let cap = self.read_merkle_cap::<F, H>(cap_height)?;
         Ok(MerkleTree {
-            leaves,
+            leaves: leaves.into(),
             digests,
             cap,
         })
@@ -469,7 +481,7 @@ pub trait Read {
     #[inline]
     fn read_target_fri_initial_proof(&mut self) -> IoResult<FriInitialTreeProofTarget> {
         let len = self.read_usize()?;
-        let mut evals_proofs = Vec::with_capacity(len);
+        let mut evals_proofs = cautious_vec(len);
 
         for _ in 0..len {
             evals_proofs.push((self.read_target_vec()?, self.read_target_merkle_proof()?));
@@ -544,7 +556,7 @@ pub trait Read {
         &mut self,
     ) -> IoResult<Vec<FriQueryRoundTarget<D>>> {
         let num_query_rounds = self.read_usize()?;
-        let mut fqrs = Vec::with_capacity(num_query_rounds);
+        let mut fqrs = cautious_vec(num_query_rounds);
         for _ in 0..num_query_rounds {
             let initial_trees_proof = self.read_target_fri_initial_proof()?;
             let num_steps = self.read_usize()?;
@@ -705,7 +717,7 @@ pub trait Read {
     fn read_selectors_info(&mut self) -> IoResult<SelectorsInfo> {
         let selector_indices = self.read_usize_vec()?;
         let groups_len = self.read_usize()?;
-        let mut groups = Vec::with_capacity(groups_len);
+        let mut groups = cautious_vec(groups_len);
         for _ in 0..groups_len {
             let start = self.read_usize()?;
             let end = self.read_usize()?;
@@ -726,7 +738,7 @@ pub trait Read {
         &mut self,
     ) -> IoResult<PolynomialBatch<F, C, D>> {
         let poly_len = self.read_usize()?;
-        let mut polynomials = Vec::with_capacity(poly_len);
+        let mut polynomials = cautious_vec(poly_len);
         for _ in 0..poly_len {
             let plen = self.read_usize()?;
             polynomials.push(PolynomialCoeffs::new(self.read_field_vec(plen)?));
@@ -767,14 +779,14 @@ pub trait Read {
         let num_lookup_polys = self.read_usize()?;
         let num_lookup_selectors = self.read_usize()?;
         let length = self.read_usize()?;
-        let mut luts = Vec::with_capacity(length);
+        let mut luts = cautious_vec(length);
 
         for _ in 0..length {
             luts.push(Arc::new(self.read_lut()?));
         }
 
         let gates_len = self.read_usize()?;
-        let mut gates = Vec::with_capacity(gates_len);
+        let mut gates = cautious_vec(gates_len);
 
         // We construct the common data without gates first,
         // to pass it as argument when reading the gates.
@@ -833,7 +845,7 @@ pub trait Read {
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<ProverOnlyCircuitData<F, C, D>> {
         let gen_len = self.read_usize()?;
-        let mut generators = Vec::with_capacity(gen_len);
+        let mut generators = cautious_vec(gen_len);
         for _ in 0..gen_len {
             generators.push(self.read_generator(generator_serializer, common_data)?);
         }
@@ -846,7 +858,7 @@ pub trait Read {
 
         let constants_sigmas_commitment = self.read_polynomial_batch()?;
         let sigmas_len = self.read_usize()?;
-        let mut sigmas = Vec::with_capacity(sigmas_len);
+        let mut sigmas = cautious_vec(sigmas_len);
         for _ in 0..sigmas_len {
             let sigma_len = self.read_usize()?;
             sigmas.push(self.read_field_vec(sigma_len)?);
@@ -863,7 +875,7 @@ pub trait Read {
         let fft_root_table = match is_some {
             true => {
                 let table_len = self.read_usize()?;
-                let mut table = Vec::with_capacity(table_len);
+                let mut table = cautious_vec(table_len);
                 for _ in 0..table_len {
                     let len = self.read_usize()?;
                     table.push(self.read_field_vec(len)?);
@@ -876,7 +888,7 @@ pub trait Read {
         let circuit_digest = self.read_hash::<F, <C as GenericConfig<D>>::Hasher>()?;
 
         let length = self.read_usize()?;
-        let mut lookup_rows = Vec::with_capacity(length);
+        let mut lookup_rows = cautious_vec(length);
         for _ in 0..length {
             lookup_rows.push(LookupWire {
                 last_lu_gate: self.read_usize()?,
@@ -886,7 +898,7 @@ pub trait Read {
         }
 
         let length = self.read_usize()?;
-        let mut lut_to_lookups = Vec::with_capacity(length);
+        let mut lut_to_lookups = cautious_vec(length);
         for _ in 0..length {
             lut_to_lookups.push(self.read_target_lut()?);
         }
@@ -1162,7 +1174,7 @@ pub trait Read {
     #[inline]
     fn read_lut(&mut self) -> IoResult<Vec<(u16, u16)>> {
         let length = self.read_usize()?;
-        let mut lut = Vec::with_capacity(length);
+        let mut lut = cautious_vec(length);
         for _ in 0..length {
             lut.push((self.read_u16()?, self.read_u16()?));
         }
@@ -1174,7 +1186,7 @@ pub trait Read {
     #[inline]
     fn read_target_lut(&mut self) -> IoResult<Lookup> {
         let length = self.read_usize()?;
-        let mut lut = Vec::with_capacity(length);
+        let mut lut = cautious_vec(length);
         for _ in 0..length {
             lut.push((self.read_target()?, self.read_target()?));
         }
@@ -2231,3 +2243,70 @@ impl Read for Buffer<'_> {
         generator_serializer.read_generator(self, common_data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::plonk::circuit_data::VerifierCircuitData;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_huge_lengths() {
+        let bytes = u64::MAX.to_le_bytes();
+        assert!(Buffer::new(&bytes).read_usize_vec().is_err());
+        assert!(Buffer::new(&bytes)
+            .read_merkle_tree::<F, PoseidonHash>()
+            .is_err());
+        assert!(Buffer::new(&bytes)
+            .read_merkle_cap::<F, PoseidonHash>(100)
+            .is_err());
+    }
+
+    /// Corrupted artifacts must be rejected, rather than panic or abort.
+    #[test]
+    fn test_corrupted_artifacts() {
+        let verifier_data = VerifierCircuitData::<F, C, D>::from_versioned_bytes(
+            include_bytes!("testdata/verifier_circuit_data_v1.bin"),
+            &DefaultGateSerializer,
+        )
+        .unwrap();
+        let proof = ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(
+            include_bytes!("testdata/proof_with_public_inputs_v1.bin"),
+            &verifier_data.common,
+        )
+        .unwrap();
+        let verifier_data_bytes = verifier_data.to_bytes(&DefaultGateSerializer).unwrap();
+        let proof_bytes = proof.to_bytes();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut corrupt = |bytes: &[u8]| {
+            let mut bytes = bytes.to_vec();
+            let i = rng.gen_range(0..bytes.len());
+            if rng.gen_bool(0.1) {
+                bytes.truncate(i);
+            } else {
+                bytes[i] ^= 1 << rng.gen_range(0..8);
+            }
+            bytes
+        };
+        for _ in 0..300 {
+            let _ = VerifierCircuitData::<F, C, D>::from_bytes(
+                corrupt(&verifier_data_bytes),
+                &DefaultGateSerializer,
+            );
+            if let Ok(proof) =
+                ProofWithPublicInputs::from_bytes(corrupt(&proof_bytes), &verifier_data.common)
+            {
+                let _ = verifier_data.verify(proof);
+            }
+        }
+    }
+}
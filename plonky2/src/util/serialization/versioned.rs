@@ -0,0 +1,398 @@
+//! A versioned, self-describing byte format for proofs and verifier data.
+//!
+//! Unlike the raw encodings of [`ProofWithPublicInputs::to_bytes`] and
+//! [`VerifierCircuitData::to_bytes`], artifacts in this format identify themselves, so that
+//! readers can reject data produced by an incompatible version or for another circuit shape
+//! instead of misinterpreting it. All integers are little-endian. An artifact consists of:
+//!
+//! - the magic bytes [`MAGIC`];
+//! - the format version, as a `u32`, currently [`FORMAT_VERSION`];
+//! - the [`ArtifactKind`], as a `u8`;
+//! - a 32-byte digest of the circuit configuration, see [`config_digest`];
+//! - a sequence of sections, each made of a `u8` identifier, a `u64` length, and a payload of
+//!   that length. Readers ignore sections they do not know, which allows adding optional
+//!   sections without breaking existing readers.
+//!
+//! Proofs have a [`SECTION_PUBLIC_INPUTS`] and a [`SECTION_PROOF`] section. Verifier data has a
+//! [`SECTION_COMMON_DATA`] and a [`SECTION_VERIFIER_ONLY_DATA`] section. The payloads use the
+//! encodings of [`Write`].
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use keccak_hash::keccak;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::util::serialization::{Buffer, GateSerializer, Read, Remaining, Write};
+
+/// The magic bytes starting every artifact.
+pub const MAGIC: [u8; 4] = *b"PLK2";
+
+/// The current version of the format.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Section holding the public inputs of a proof.
+pub const SECTION_PUBLIC_INPUTS: u8 = 0;
+/// Section holding a proof, without its public inputs.
+pub const SECTION_PROOF: u8 = 1;
+/// Section holding the [`CommonCircuitData`] of a circuit.
+pub const SECTION_COMMON_DATA: u8 = 2;
+/// Section holding the [`VerifierOnlyCircuitData`](crate::plonk::circuit_data::VerifierOnlyCircuitData)
+/// of a circuit.
+pub const SECTION_VERIFIER_ONLY_DATA: u8 = 3;
+
+/// The kind of data held by an artifact.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum ArtifactKind {
+    /// A [`ProofWithPublicInputs`].
+    ProofWithPublicInputs = 0,
+    /// A [`VerifierCircuitData`].
+    VerifierCircuitData = 1,
+}
+
+impl TryFrom<u8> for ArtifactKind {
+    type Error = anyhow::Error;
+
+    fn try_from(kind: u8) -> Result<Self> {
+        match kind {
+            0 => Ok(Self::ProofWithPublicInputs),
+            1 => Ok(Self::VerifierCircuitData),
+            _ => Err(anyhow!("Unknown artifact kind {}", kind)),
+        }
+    }
+}
+
+/// Computes a digest of the configuration of a circuit, i.e. its [`CircuitConfig`], its
+/// [`FriParams`] (which include the degree of the circuit) and its number of public inputs.
+///
+/// [`CircuitConfig`]: crate::plonk::circuit_data::CircuitConfig
+/// [`FriParams`]: crate::fri::FriParams
+pub fn config_digest<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> [u8; 32] {
+    let mut buffer = Vec::new();
+    buffer
+        .write_circuit_config(&common_data.config)
+        .and_then(|()| buffer.write_fri_params(&common_data.fri_params))
+        .and_then(|()| buffer.write_usize(common_data.num_public_inputs))
+        .expect("Writing to a byte-vector cannot fail.");
+    keccak(buffer).to_fixed_bytes()
+}
+
+fn write_header(buffer: &mut Vec<u8>, kind: ArtifactKind, digest: [u8; 32]) {
+    buffer.extend_from_slice(&MAGIC);
+    buffer.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    buffer.push(kind as u8);
+    buffer.extend_from_slice(&digest);
+}
+
+fn write_section(buffer: &mut Vec<u8>, id: u8, payload: &[u8]) {
+    buffer.push(id);
+    buffer.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    buffer.extend_from_slice(payload);
+}
+
+/// The parsed header and sections of an artifact.
+struct Artifact<'a> {
+    kind: ArtifactKind,
+    digest: [u8; 32],
+    sections: Vec<(u8, &'a [u8])>,
+}
+
+/// Splits the first `n` bytes off `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    ensure!(bytes.len() >= n, "Truncated artifact");
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+impl<'a> Artifact<'a> {
+    fn parse(mut bytes: &'a [u8]) -> Result<Self> {
+        ensure!(take(&mut bytes, 4)? == MAGIC, "Not a plonky2 artifact");
+        let version = u32::from_le_bytes(take(&mut bytes, 4)?.try_into()?);
+        ensure!(
+            version <= FORMAT_VERSION,
+            "Unsupported format version {}, the latest supported one is {}",
+            version,
+            FORMAT_VERSION
+        );
+        let kind = ArtifactKind::try_from(take(&mut bytes, 1)?[0])?;
+        let digest = take(&mut bytes, 32)?.try_into()?;
+
+        let mut sections: Vec<(u8, &[u8])> = Vec::new();
+        while !bytes.is_empty() {
+            let id = take(&mut bytes, 1)?[0];
+            let len = u64::from_le_bytes(take(&mut bytes, 8)?.try_into()?);
+            ensure!(
+                sections.iter().all(|&(i, _)| i != id),
+                "Duplicate section {}",
+                id
+            );
+            sections.push((id, take(&mut bytes, usize::try_from(len)?)?));
+        }
+
+        Ok(Self {
+            kind,
+            digest,
+            sections,
+        })
+    }
+
+    fn expect(&self, kind: ArtifactKind, digest: Option<[u8; 32]>) -> Result<()> {
+        ensure!(
+            self.kind == kind,
+            "Expected an artifact of kind {:?}, got {:?}",
+            kind,
+            self.kind
+        );
+        if let Some(digest) = digest {
+            ensure!(
+                self.digest == digest,
+                "The artifact was produced for another circuit configuration"
+            );
+        }
+        Ok(())
+    }
+
+    fn section(&self, id: u8) -> Result<Buffer<'a>> {
+        match self.sections.iter().find(|&&(i, _)| i == id) {
+            Some(&(_, payload)) => Ok(Buffer::new(payload)),
+            None => bail!("Missing section {}", id),
+        }
+    }
+}
+
+/// Reads a value from a section, ensuring that it spans the whole section.
+fn read_section<'a, T>(
+    artifact: &Artifact<'a>,
+    id: u8,
+    read: impl FnOnce(&mut Buffer<'a>) -> Result<T>,
+) -> Result<T> {
+    let mut buffer = artifact.section(id)?;
+    let value = read(&mut buffer)?;
+    ensure!(buffer.is_empty(), "Trailing bytes in section {}", id);
+    Ok(value)
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProofWithPublicInputs<F, C, D>
+{
+    /// Serializes this proof in the versioned format described in
+    /// [`versioned`](crate::util::serialization::versioned).
+    pub fn to_versioned_bytes(&self, common_data: &CommonCircuitData<F, D>) -> Vec<u8> {
+        let mut public_inputs = Vec::new();
+        let mut proof = Vec::new();
+        public_inputs
+            .write_usize(self.public_inputs.len())
+            .and_then(|()| public_inputs.write_field_vec(&self.public_inputs))
+            .and_then(|()| proof.write_proof(&self.proof))
+            .expect("Writing to a byte-vector cannot fail.");
+
+        let mut buffer = Vec::new();
+        write_header(
+            &mut buffer,
+            ArtifactKind::ProofWithPublicInputs,
+            config_digest(common_data),
+        );
+        write_section(&mut buffer, SECTION_PUBLIC_INPUTS, &public_inputs);
+        write_section(&mut buffer, SECTION_PROOF, &proof);
+        buffer
+    }
+
+    /// Deserializes a proof in the versioned format described in
+    /// [`versioned`](crate::util::serialization::versioned), checking that it was produced for
+    /// a circuit with the same configuration as `common_data`.
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self> {
+        let artifact = Artifact::parse(bytes)?;
+        artifact.expect(
+            ArtifactKind::ProofWithPublicInputs,
+            Some(config_digest(common_data)),
+        )?;
+
+        let public_inputs = read_section(&artifact, SECTION_PUBLIC_INPUTS, |buffer| {
+            let len = buffer.read_usize().map_err(anyhow::Error::msg)?;
+            buffer.read_field_vec(len).map_err(anyhow::Error::msg)
+        })?;
+        let proof = read_section(&artifact, SECTION_PROOF, |buffer| {
+            buffer.read_proof(common_data).map_err(anyhow::Error::msg)
+        })?;
+        Ok(Self {
+            proof,
+            public_inputs,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    VerifierCircuitData<F, C, D>
+{
+    /// Serializes this verifier data in the versioned format described in
+    /// [`versioned`](crate::util::serialization::versioned).
+    pub fn to_versioned_bytes(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> Result<Vec<u8>> {
+        let mut common = Vec::new();
+        let mut verifier_only = Vec::new();
+        common
+            .write_common_circuit_data(&self.common, gate_serializer)
+            .and_then(|()| verifier_only.write_verifier_only_circuit_data(&self.verifier_only))
+            .map_err(anyhow::Error::msg)?;
+
+        let mut buffer = Vec::new();
+        write_header(
+            &mut buffer,
+            ArtifactKind::VerifierCircuitData,
+            config_digest(&self.common),
+        );
+        write_section(&mut buffer, SECTION_COMMON_DATA, &common);
+        write_section(&mut buffer, SECTION_VERIFIER_ONLY_DATA, &verifier_only);
+        Ok(buffer)
+    }
+
+    /// Deserializes verifier data in the versioned format described in
+    /// [`versioned`](crate::util::serialization::versioned).
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> Result<Self> {
+        let artifact = Artifact::parse(bytes)?;
+        artifact.expect(ArtifactKind::VerifierCircuitData, None)?;
+
+        let common = read_section(&artifact, SECTION_COMMON_DATA, |buffer| {
+            buffer
+                .read_common_circuit_data(gate_serializer)
+                .map_err(anyhow::Error::msg)
+        })?;
+        ensure!(
+            artifact.digest == config_digest(&common),
+            "The configuration digest does not match the circuit data"
+        );
+        let verifier_only = read_section(&artifact, SECTION_VERIFIER_ONLY_DATA, |buffer| {
+            buffer
+                .read_verifier_only_circuit_data()
+                .map_err(anyhow::Error::msg)
+        })?;
+        Ok(Self {
+            verifier_only,
+            common,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::util::serialization::DefaultGateSerializer;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves knowledge of a non-trivial factorization of the public input of a small circuit.
+    fn prove_factorization() -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        // Use few query rounds, and hence low security, to keep the golden proof small.
+        let mut config = CircuitConfig::standard_recursion_config();
+        config.fri_config.num_query_rounds = 2;
+        config.security_bits = 20;
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let product = builder.mul(x, y);
+        builder.register_public_input(product);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u32(3))?;
+        pw.set_target(y, F::from_canonical_u32(7))?;
+        let proof = data.prove(pw)?;
+        Ok((data, proof))
+    }
+
+    #[test]
+    fn test_versioned_round_trip() -> Result<()> {
+        let (data, proof) = prove_factorization()?;
+        let verifier_data = data.verifier_data();
+
+        let bytes = proof.to_versioned_bytes(&data.common);
+        let decoded_proof = ProofWithPublicInputs::from_versioned_bytes(&bytes, &data.common)?;
+        assert_eq!(decoded_proof, proof);
+
+        let bytes = verifier_data.to_versioned_bytes(&DefaultGateSerializer)?;
+        let decoded_verifier_data =
+            VerifierCircuitData::<F, C, D>::from_versioned_bytes(&bytes, &DefaultGateSerializer)?;
+        assert_eq!(decoded_verifier_data, verifier_data);
+        decoded_verifier_data.verify(decoded_proof)
+    }
+
+    #[test]
+    fn test_versioned_golden_files() -> Result<()> {
+        // Artifacts written by version 1 of the format, which must remain readable.
+        let verifier_data_bytes = include_bytes!("testdata/verifier_circuit_data_v1.bin");
+        let proof_bytes = include_bytes!("testdata/proof_with_public_inputs_v1.bin");
+
+        let verifier_data = VerifierCircuitData::<F, C, D>::from_versioned_bytes(
+            verifier_data_bytes,
+            &DefaultGateSerializer,
+        )?;
+        let proof =
+            ProofWithPublicInputs::from_versioned_bytes(proof_bytes, &verifier_data.common)?;
+        assert_eq!(proof.public_inputs, [F::from_canonical_u32(21)]);
+
+        assert_eq!(
+            verifier_data.to_versioned_bytes(&DefaultGateSerializer)?,
+            verifier_data_bytes
+        );
+        assert_eq!(proof.to_versioned_bytes(&verifier_data.common), proof_bytes);
+        verifier_data.verify(proof)
+    }
+
+    #[test]
+    fn test_versioned_invalid_artifacts() -> Result<()> {
+        let (data, proof) = prove_factorization()?;
+        let bytes = proof.to_versioned_bytes(&data.common);
+        let read = |bytes: &[u8]| {
+            ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(bytes, &data.common)
+        };
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 1;
+        assert!(read(&bad_magic).is_err());
+
+        let mut newer_version = bytes.clone();
+        newer_version[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(read(&newer_version).is_err());
+
+        let mut other_config = bytes.clone();
+        other_config[9] ^= 1;
+        assert!(read(&other_config).is_err());
+
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+
+        let verifier_data_bytes = data
+            .verifier_data()
+            .to_versioned_bytes(&DefaultGateSerializer)?;
+        assert!(read(&verifier_data_bytes).is_err());
+
+        // Unknown sections are skipped.
+        let mut extra_section = bytes.clone();
+        write_section(&mut extra_section, u8::MAX, &[1, 2, 3]);
+        assert_eq!(read(&extra_section)?, proof);
+        Ok(())
+    }
+}
@@ -0,0 +1,571 @@
+//! Symbolic recovery of the arithmetic performed through a [`CircuitBuilder`].
+//!
+//! The builder memoizes every extension arithmetic operation
+//! `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend` it adds to the circuit.
+//! Walking these operations backwards from some output targets yields the expressions
+//! computing them in terms of some input targets, which allows inspecting constraints
+//! written against the builder (e.g. the recursive constraints of a gate or a STARK)
+//! without evaluating them.
+//!
+//! For audits, [`SymbolicDag::expand`] expands such expressions into [`SymbolicPolynomial`]s,
+//! and a [`ConstraintReport`] renders a set of constraints as readable polynomials over named
+//! variables, as text (through its [`Display`] implementation) or with any `serde` backend, so
+//! that the implemented constraints can be diffed against a specification.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A node of a [`SymbolicDag`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SymbolicNode<F: RichField + Extendable<D>, const D: usize> {
+    /// The input at the given index in the list of inputs of the DAG.
+    Input(usize),
+    /// A constant of the extension field.
+    Constant(F::Extension),
+    /// A target which is neither an input, a constant, nor the output of an arithmetic
+    /// operation, e.g. a value set by a witness generator.
+    Opaque(ExtensionTarget<D>),
+    /// `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend`, where the operands
+    /// are indices of other nodes of the DAG.
+    Arithmetic {
+        const_0: F,
+        const_1: F,
+        multiplicand_0: usize,
+        multiplicand_1: usize,
+        addend: usize,
+    },
+}
+
+/// A directed acyclic graph of the arithmetic operations computing some targets of a circuit.
+///
+/// Nodes are topologically sorted: the operands of a node always come before it.
+#[derive(Clone, Debug)]
+pub struct SymbolicDag<F: RichField + Extendable<D>, const D: usize> {
+    nodes: Vec<SymbolicNode<F, D>>,
+    outputs: Vec<usize>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SymbolicDag<F, D> {
+    /// Returns all the nodes of this DAG.
+    pub fn nodes(&self) -> &[SymbolicNode<F, D>] {
+        &self.nodes
+    }
+
+    /// Returns the indices of the nodes computing the requested outputs, in order.
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// Computes the degree of each node, given the degree of each input.
+    ///
+    /// Constants have degree 0. Nodes depending on an [`SymbolicNode::Opaque`] value have
+    /// an unknown degree, represented by `None`.
+    pub fn degrees(&self, input_degrees: &[usize]) -> Vec<Option<usize>> {
+        let mut degrees: Vec<Option<usize>> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let degree = match *node {
+                SymbolicNode::Input(i) => Some(input_degrees[i]),
+                SymbolicNode::Constant(_) => Some(0),
+                SymbolicNode::Opaque(_) => None,
+                SymbolicNode::Arithmetic {
+                    const_0,
+                    const_1,
+                    multiplicand_0,
+                    multiplicand_1,
+                    addend,
+                } => {
+                    let product = if const_0.is_zero() {
+                        Some(0)
+                    } else {
+                        degrees[multiplicand_0]
+                            .zip(degrees[multiplicand_1])
+                            .map(|(d0, d1)| d0 + d1)
+                    };
+                    let sum = if const_1.is_zero() {
+                        Some(0)
+                    } else {
+                        degrees[addend]
+                    };
+                    product.zip(sum).map(|(d0, d1)| d0.max(d1))
+                }
+            };
+            degrees.push(degree);
+        }
+        degrees
+    }
+
+    /// Returns the sorted indices of the inputs that the given node depends on.
+    pub fn input_dependencies(&self, node: usize) -> Vec<usize> {
+        let mut visited = vec![false; node + 1];
+        let mut inputs = Vec::new();
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            if visited[n] {
+                continue;
+            }
+            visited[n] = true;
+            match self.nodes[n] {
+                SymbolicNode::Input(i) => inputs.push(i),
+                SymbolicNode::Arithmetic {
+                    const_0,
+                    const_1,
+                    multiplicand_0,
+                    multiplicand_1,
+                    addend,
+                } => {
+                    if !const_0.is_zero() {
+                        stack.push(multiplicand_0);
+                        stack.push(multiplicand_1);
+                    }
+                    if !const_1.is_zero() {
+                        stack.push(addend);
+                    }
+                }
+                SymbolicNode::Constant(_) | SymbolicNode::Opaque(_) => (),
+            }
+        }
+        inputs.sort_unstable();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Expands each output of this DAG into a [`SymbolicPolynomial`] over its leaves, i.e. its
+    /// [`SymbolicNode::Input`] and [`SymbolicNode::Opaque`] nodes.
+    ///
+    /// Expanding can blow up exponentially, so an output is `None` if it, or any intermediate
+    /// value it depends on, has more than `max_terms` terms.
+    pub fn expand(&self, max_terms: usize) -> Vec<Option<SymbolicPolynomial<F, D>>> {
+        let mut polys: Vec<Option<SymbolicPolynomial<F, D>>> = Vec::with_capacity(self.nodes.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            let poly = match *node {
+                SymbolicNode::Input(input) => {
+                    Some(SymbolicPolynomial::variable(SymbolicVariable::Input(input)))
+                }
+                SymbolicNode::Opaque(_) => {
+                    Some(SymbolicPolynomial::variable(SymbolicVariable::Opaque(i)))
+                }
+                SymbolicNode::Constant(c) => Some(SymbolicPolynomial::constant(c)),
+                SymbolicNode::Arithmetic {
+                    const_0,
+                    const_1,
+                    multiplicand_0,
+                    multiplicand_1,
+                    addend,
+                } => {
+                    let product = if const_0.is_zero() {
+                        Some(SymbolicPolynomial::default())
+                    } else {
+                        polys[multiplicand_0]
+                            .as_ref()
+                            .zip(polys[multiplicand_1].as_ref())
+                            .and_then(|(p0, p1)| p0.mul(p1, max_terms))
+                    };
+                    product.and_then(|mut poly| {
+                        poly.scale(const_0);
+                        if !const_1.is_zero() {
+                            poly.add_scaled(polys[addend].as_ref()?, const_1);
+                        }
+                        (poly.num_terms() <= max_terms).then_some(poly)
+                    })
+                }
+            };
+            polys.push(poly);
+        }
+        self.outputs.iter().map(|&o| polys[o].clone()).collect()
+    }
+}
+
+/// A variable of a [`SymbolicPolynomial`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SymbolicVariable {
+    /// The input at the given index in the list of inputs of the DAG.
+    Input(usize),
+    /// The [`SymbolicNode::Opaque`] node at the given index in the DAG.
+    Opaque(usize),
+}
+
+impl SymbolicVariable {
+    /// Returns the name of this variable: the name of the input for an input, and `opaque[i]`
+    /// for the opaque node of index `i`.
+    pub fn name(&self, input_names: &[String]) -> String {
+        match *self {
+            Self::Input(i) => input_names[i].clone(),
+            Self::Opaque(node) => format!("opaque[{}]", node),
+        }
+    }
+}
+
+/// A polynomial over the inputs and opaque values of a [`SymbolicDag`], in expanded form, as output
+/// by [`SymbolicDag::expand`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolicPolynomial<F: RichField + Extendable<D>, const D: usize> {
+    /// Maps each monomial, given by its sorted variables with repetition, to its nonzero
+    /// coefficient.
+    terms: BTreeMap<Vec<SymbolicVariable>, F::Extension>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for SymbolicPolynomial<F, D> {
+    fn default() -> Self {
+        Self {
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SymbolicPolynomial<F, D> {
+    fn constant(c: F::Extension) -> Self {
+        let mut poly = Self::default();
+        if !c.is_zero() {
+            poly.terms.insert(Vec::new(), c);
+        }
+        poly
+    }
+
+    fn variable(variable: SymbolicVariable) -> Self {
+        let mut poly = Self::default();
+        poly.terms.insert(vec![variable], F::Extension::ONE);
+        poly
+    }
+
+    /// Returns the number of terms of this polynomial.
+    pub fn num_terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Returns the total degree of this polynomial, which is 0 for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.terms.keys().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Returns the terms of this polynomial, as pairs of monomials and coefficients. A monomial
+    /// is given by its sorted variables, with repetition.
+    pub fn terms(&self) -> impl Iterator<Item = (&[SymbolicVariable], F::Extension)> {
+        self.terms.iter().map(|(m, &c)| (m.as_slice(), c))
+    }
+
+    fn scale(&mut self, c: F) {
+        if c.is_zero() {
+            self.terms.clear();
+        } else {
+            self.terms
+                .values_mut()
+                .for_each(|coeff| *coeff = coeff.scalar_mul(c));
+        }
+    }
+
+    fn add_term(&mut self, monomial: Vec<SymbolicVariable>, c: F::Extension) {
+        match self.terms.entry(monomial) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += c;
+                if entry.get().is_zero() {
+                    entry.remove();
+                }
+            }
+            Entry::Vacant(entry) => {
+                if !c.is_zero() {
+                    entry.insert(c);
+                }
+            }
+        }
+    }
+
+    fn add_scaled(&mut self, other: &Self, c: F) {
+        for (monomial, &coeff) in &other.terms {
+            self.add_term(monomial.clone(), coeff.scalar_mul(c));
+        }
+    }
+
+    fn mul(&self, other: &Self, max_terms: usize) -> Option<Self> {
+        let mut product = Self::default();
+        for ((m0, &c0), (m1, &c1)) in self.terms.iter().cartesian_product(&other.terms) {
+            let monomial = m0.iter().merge(m1).copied().collect();
+            product.add_term(monomial, c0 * c1);
+            if product.num_terms() > max_terms {
+                return None;
+            }
+        }
+        Some(product)
+    }
+
+    /// Renders this polynomial, naming each variable with `name`.
+    ///
+    /// Terms are sorted by decreasing degree, then by their variables, with inputs in order
+    /// followed by opaque values. Coefficients in the base field are printed as
+    /// signed integers of minimal absolute value, and other coefficients as the list of their
+    /// coefficients over the base field.
+    pub fn render(&self, name: impl Fn(SymbolicVariable) -> String) -> String {
+        let terms = self
+            .terms
+            .iter()
+            .sorted_by(|(m0, _), (m1, _)| m1.len().cmp(&m0.len()).then(m0.cmp(m1)));
+
+        let mut rendered = String::new();
+        for (i, (monomial, &coeff)) in terms.enumerate() {
+            let (negative, magnitude) = render_coefficient::<F, D>(coeff);
+            rendered += match (i, negative) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+
+            let variables = monomial
+                .iter()
+                .dedup_with_count()
+                .map(|(power, &variable)| match power {
+                    1 => name(variable),
+                    _ => format!("{}^{}", name(variable), power),
+                })
+                .join("*");
+            rendered += &match (magnitude.as_str(), variables.is_empty()) {
+                (_, true) => magnitude,
+                ("1", false) => variables,
+                (_, false) => format!("{}*{}", magnitude, variables),
+            };
+        }
+
+        if rendered.is_empty() {
+            rendered.push('0');
+        }
+        rendered
+    }
+}
+
+/// Returns whether the given coefficient is rendered as a negative value, along with its rendered
+/// absolute value.
+fn render_coefficient<F: RichField + Extendable<D>, const D: usize>(
+    c: F::Extension,
+) -> (bool, String) {
+    let coeffs = c.to_basefield_array();
+    if coeffs[1..].iter().all(|c| c.is_zero()) {
+        let value = coeffs[0].to_canonical_u64();
+        if value > F::ORDER / 2 {
+            (true, (F::ORDER - value).to_string())
+        } else {
+            (false, value.to_string())
+        }
+    } else {
+        let coeffs = coeffs.iter().map(|c| c.to_canonical_u64()).join(", ");
+        (false, format!("[{}]", coeffs))
+    }
+}
+
+/// A constraint of a [`ConstraintReport`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RenderedConstraint {
+    /// The set of rows on which the constraint is enforced, if it does not apply everywhere.
+    pub filter: Option<String>,
+    /// The degree of the constraint, or `None` if it depends on opaque values.
+    pub degree: Option<usize>,
+    /// The constraint, rendered as an expanded polynomial which must vanish. This is `None` if
+    /// the expansion exceeds the maximal number of terms.
+    pub polynomial: Option<String>,
+}
+
+/// A set of constraints rendered as readable polynomials over named variables.
+///
+/// The text rendering of a report, given by its [`Display`] implementation, is line based so
+/// that reports can be diffed; it can also be serialized with any `serde` backend, e.g. to JSON.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ConstraintReport {
+    /// The name of the constrained object, e.g. a gate or a STARK.
+    pub name: String,
+    /// The names of the variables the constraints are expressed in.
+    pub variables: Vec<String>,
+    /// The constraints, in the order they are emitted.
+    pub constraints: Vec<RenderedConstraint>,
+}
+
+impl ConstraintReport {
+    /// Renders the outputs of `dag` as unfiltered constraints over its inputs, which are named by
+    /// `variables` and have the given degrees. Outputs with more than `max_terms` terms, once
+    /// expanded, are not rendered.
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        name: String,
+        variables: Vec<String>,
+        dag: &SymbolicDag<F, D>,
+        input_degrees: &[usize],
+        max_terms: usize,
+    ) -> Self {
+        let degrees = dag.degrees(input_degrees);
+        let constraints = dag
+            .outputs()
+            .iter()
+            .zip(dag.expand(max_terms))
+            .map(|(&output, poly)| RenderedConstraint {
+                filter: None,
+                degree: degrees[output],
+                polynomial: poly.map(|p| p.render(|v| v.name(&variables))),
+            })
+            .collect();
+        Self {
+            name,
+            variables,
+            constraints,
+        }
+    }
+}
+
+impl Display for ConstraintReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}", self.name)?;
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            write!(f, "constraint {}", i)?;
+            if let Some(filter) = &constraint.filter {
+                write!(f, " [{}]", filter)?;
+            }
+            match constraint.degree {
+                Some(degree) => write!(f, " (degree {})", degree)?,
+                None => write!(f, " (degree unknown)")?,
+            }
+            match &constraint.polynomial {
+                Some(poly) => writeln!(f, ": {} = 0", poly)?,
+                None => writeln!(f, ": too many terms")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Recovers the arithmetic operations computing `outputs` from `inputs`, as a [`SymbolicDag`].
+    ///
+    /// Only operations added through [`CircuitBuilder::arithmetic_extension`] (which underlies
+    /// all extension arithmetic methods of the builder) are traversed; any other target reached
+    /// is reported as a constant or as an opaque value.
+    pub fn symbolic_dag(
+        &self,
+        inputs: &[ExtensionTarget<D>],
+        outputs: &[ExtensionTarget<D>],
+    ) -> SymbolicDag<F, D> {
+        let operations = self
+            .arithmetic_results
+            .iter()
+            .map(|(operation, &result)| (result, operation))
+            .collect::<HashMap<_, _>>();
+        let input_indices = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, &input)| (input, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut nodes = Vec::new();
+        let mut node_indices = HashMap::<ExtensionTarget<D>, usize>::new();
+
+        // Iterative post-order traversal, as arithmetic chains can be arbitrarily deep.
+        let mut stack = outputs
+            .iter()
+            .rev()
+            .map(|&t| (t, false))
+            .collect::<Vec<_>>();
+        while let Some((target, operands_visited)) = stack.pop() {
+            if node_indices.contains_key(&target) {
+                continue;
+            }
+
+            let node = if let Some(&i) = input_indices.get(&target) {
+                SymbolicNode::Input(i)
+            } else if let Some(c) = self.target_as_constant_ext(target) {
+                SymbolicNode::Constant(c)
+            } else if let Some(operation) = operations.get(&target) {
+                let operands = [
+                    operation.multiplicand_0,
+                    operation.multiplicand_1,
+                    operation.addend,
+                ];
+                if !operands_visited {
+                    stack.push((target, true));
+                    stack.extend(operands.iter().map(|&t| (t, false)));
+                    continue;
+                }
+                SymbolicNode::Arithmetic {
+                    const_0: operation.const_0,
+                    const_1: operation.const_1,
+                    multiplicand_0: node_indices[&operands[0]],
+                    multiplicand_1: node_indices[&operands[1]],
+                    addend: node_indices[&operands[2]],
+                }
+            } else {
+                SymbolicNode::Opaque(target)
+            };
+
+            node_indices.insert(target, nodes.len());
+            nodes.push(node);
+        }
+
+        SymbolicDag {
+            outputs: outputs.iter().map(|t| node_indices[t]).collect(),
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_symbolic_dag() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let inputs = builder.add_virtual_extension_targets(3);
+        let (x, y, z) = (inputs[0], inputs[1], inputs[2]);
+
+        // x^2 * y + 3 * x
+        let x_sq = builder.square_extension(x);
+        let three = F::from_canonical_u32(3);
+        let three_x = builder.mul_const_extension(three, x);
+        let output = builder.mul_add_extension(x_sq, y, three_x);
+        let opaque = builder.inverse_extension(z);
+
+        let dag = builder.symbolic_dag(&inputs, &[output, opaque]);
+        let degrees = dag.degrees(&[1, 1, 1]);
+
+        let output = dag.outputs()[0];
+        assert_eq!(degrees[output], Some(3));
+        assert_eq!(dag.input_dependencies(output), vec![0, 1]);
+
+        let opaque = dag.outputs()[1];
+        assert_eq!(degrees[opaque], None);
+        assert!(dag.input_dependencies(opaque).is_empty());
+
+        let names = ["x", "y", "z"].map(String::from);
+        let polys = dag.expand(10);
+        let render = |i: usize| polys[i].as_ref().unwrap().render(|v| v.name(&names));
+        assert_eq!(render(0), "x^2*y + 3*x");
+        assert_eq!(polys[0].as_ref().unwrap().degree(), 3);
+        assert_eq!(render(1), format!("opaque[{}]", opaque));
+
+        // (x + y)^3 has 4 terms.
+        let sum = builder.add_extension(x, y);
+        let cube = builder.exp_u64_extension(sum, 3);
+        let dag = builder.symbolic_dag(&inputs, &[cube]);
+        assert_eq!(dag.expand(4)[0].as_ref().unwrap().num_terms(), 4);
+        assert!(dag.expand(3)[0].is_none());
+    }
+}
@@ -1,7 +1,12 @@
 use log::{log, Level};
 #[cfg(feature = "timing")]
+use serde::Serialize;
+#[cfg(feature = "timing")]
 use web_time::{Duration, Instant};
 
+#[cfg(feature = "timing")]
+use crate::util::memory;
+
 /// The hierarchy of scopes, and the time consumed by each one. Useful for profiling.
 #[cfg(feature = "timing")]
 #[derive(Debug)]
@@ -16,12 +21,82 @@ pub struct TimingTree {
     exit_time: Option<Instant>,
     /// Any child scopes.
     children: Vec<TimingTree>,
+    /// The memory usage of this scope, if memory is being tracked.
+    memory: Option<MemoryUsage>,
+    /// The running peak allocation of this scope while it is open, if memory is being tracked.
+    peak_scope: Option<memory::PeakScope>,
 }
 
 #[cfg(not(feature = "timing"))]
 #[derive(Debug)]
 pub struct TimingTree(Level);
 
+/// The memory usage of a scope of a [`TimingTree`] created with
+/// [`TimingTree::new_tracking_memory`]. Allocations are only accounted for if
+/// [`TrackingAllocator`](memory::TrackingAllocator) is the global allocator.
+#[cfg(feature = "timing")]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct MemoryUsage {
+    /// The number of bytes allocated when the scope was created.
+    pub allocated_on_enter: usize,
+    /// The number of bytes allocated when the scope was destroyed, or 0 if it is still open.
+    pub allocated_on_exit: usize,
+    /// The peak number of bytes allocated while the scope was open.
+    pub peak_allocated: usize,
+    /// The peak resident set size of the process when the scope was destroyed, if available.
+    pub peak_rss: Option<usize>,
+}
+
+/// A scope of a [`TimingTree`], with times in microseconds relative to the start of the root
+/// scope, as output by [`TimingTree::spans`].
+#[cfg(feature = "timing")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TimingSpan {
+    /// The name of this scope.
+    pub name: String,
+    /// The time when this scope was created.
+    pub start_us: f64,
+    /// The time spent in this scope, up to now if it is still open.
+    pub duration_us: f64,
+    /// The memory usage of this scope, if memory is being tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryUsage>,
+    /// Any child scopes.
+    pub children: Vec<TimingSpan>,
+}
+
+/// A complete event of the Chrome trace event format, understood by `chrome://tracing` and
+/// Perfetto.
+#[cfg(feature = "timing")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChromeTraceEvent {
+    /// The name of the scope.
+    pub name: String,
+    /// The event type, always `X` for complete events.
+    pub ph: &'static str,
+    /// The start time of the scope, in microseconds.
+    pub ts: f64,
+    /// The duration of the scope, in microseconds.
+    pub dur: f64,
+    /// The process ID.
+    pub pid: u64,
+    /// The thread ID, under which the scope is displayed.
+    pub tid: u64,
+}
+
+/// A trace in the Chrome trace event format, as output by [`TimingTree::chrome_trace`].
+///
+/// Serialized to JSON (e.g. with `serde_json`), it can be opened in `chrome://tracing` or
+/// <https://ui.perfetto.dev>. Events of several trees, e.g. one per thread, can be combined
+/// through [`TimingTree::chrome_trace_events`].
+#[cfg(feature = "timing")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ChromeTrace {
+    /// The events of the trace.
+    #[serde(rename = "traceEvents")]
+    pub trace_events: Vec<ChromeTraceEvent>,
+}
+
 #[cfg(feature = "timing")]
 impl Default for TimingTree {
     fn default() -> Self {
@@ -39,12 +114,41 @@ impl Default for TimingTree {
 impl TimingTree {
     #[cfg(feature = "timing")]
     pub fn new(root_name: &str, level: Level) -> Self {
+        Self::new_scope(root_name.to_string(), level, false)
+    }
+
+    /// Creates a tree whose scopes also record their [`MemoryUsage`]. See [`memory`] for how to
+    /// enable allocation accounting.
+    #[cfg(feature = "timing")]
+    pub fn new_tracking_memory(root_name: &str, level: Level) -> Self {
+        Self::new_scope(root_name.to_string(), level, true)
+    }
+
+    #[cfg(not(feature = "timing"))]
+    pub fn new_tracking_memory(root_name: &str, level: Level) -> Self {
+        Self::new(root_name, level)
+    }
+
+    #[cfg(feature = "timing")]
+    fn new_scope(name: String, level: Level, track_memory: bool) -> Self {
+        let (memory, peak_scope) = if track_memory {
+            let peak_scope = memory::PeakScope::enter();
+            let usage = MemoryUsage {
+                allocated_on_enter: memory::allocated_bytes(),
+                ..MemoryUsage::default()
+            };
+            (Some(usage), Some(peak_scope))
+        } else {
+            (None, None)
+        };
         Self {
-            name: root_name.to_string(),
+            name,
             level,
             enter_time: Instant::now(),
             exit_time: None,
             children: vec![],
+            memory,
+            peak_scope,
         }
     }
 
@@ -91,13 +195,11 @@ impl TimingTree {
             }
         }
 
-        self.children.push(TimingTree {
-            name: ctx.to_string(),
+        self.children.push(TimingTree::new_scope(
+            ctx.to_string(),
             level,
-            enter_time: Instant::now(),
-            exit_time: None,
-            children: vec![],
-        })
+            self.memory.is_some(),
+        ))
     }
 
     #[cfg(not(feature = "timing"))]
@@ -116,6 +218,14 @@ impl TimingTree {
         }
 
         self.exit_time = Some(Instant::now());
+        if let Some(usage) = &mut self.memory {
+            usage.allocated_on_exit = memory::allocated_bytes();
+            usage.peak_allocated = self
+                .peak_scope
+                .take()
+                .map_or(usage.allocated_on_exit, |peak_scope| peak_scope.peak());
+            usage.peak_rss = memory::peak_rss_bytes();
+        }
     }
 
     #[cfg(not(feature = "timing"))]
@@ -136,6 +246,9 @@ impl TimingTree {
             level: self.level,
             enter_time: self.enter_time,
             exit_time: self.exit_time,
+            memory: self.memory,
+            // The peak of an open scope is only recorded in the original tree.
+            peak_scope: None,
             children: self
                 .children
                 .iter()
@@ -158,15 +271,78 @@ impl TimingTree {
         );
     }
 
+    /// Outputs the hierarchy of scopes of this tree.
+    #[cfg(feature = "timing")]
+    pub fn spans(&self) -> TimingSpan {
+        self.spans_helper(self.enter_time)
+    }
+
+    #[cfg(feature = "timing")]
+    fn spans_helper(&self, epoch: Instant) -> TimingSpan {
+        TimingSpan {
+            name: self.name.clone(),
+            start_us: micros(self.enter_time.duration_since(epoch)),
+            duration_us: micros(self.duration()),
+            memory: self.memory,
+            children: self
+                .children
+                .iter()
+                .map(|c| c.spans_helper(epoch))
+                .collect(),
+        }
+    }
+
+    /// Outputs the scopes of this tree as a trace in the Chrome trace event format.
+    #[cfg(feature = "timing")]
+    pub fn chrome_trace(&self) -> ChromeTrace {
+        ChromeTrace {
+            trace_events: self.chrome_trace_events(self.enter_time, 0),
+        }
+    }
+
+    /// Outputs the scopes of this tree as Chrome trace events on thread `tid`, with times relative
+    /// to `epoch`. Using the same epoch for several trees allows merging their events in a single
+    /// [`ChromeTrace`].
+    #[cfg(feature = "timing")]
+    pub fn chrome_trace_events(&self, epoch: Instant, tid: u64) -> Vec<ChromeTraceEvent> {
+        let mut events = Vec::new();
+        self.chrome_trace_helper(epoch, tid, &mut events);
+        events
+    }
+
+    #[cfg(feature = "timing")]
+    fn chrome_trace_helper(&self, epoch: Instant, tid: u64, events: &mut Vec<ChromeTraceEvent>) {
+        events.push(ChromeTraceEvent {
+            name: self.name.clone(),
+            ph: "X",
+            ts: micros(self.enter_time.duration_since(epoch)),
+            dur: micros(self.duration()),
+            pid: 0,
+            tid,
+        });
+        for child in &self.children {
+            child.chrome_trace_helper(epoch, tid, events);
+        }
+    }
+
     #[cfg(feature = "timing")]
     fn print_helper(&self, depth: usize) {
         let prefix = "| ".repeat(depth);
+        let memory = match self.memory {
+            Some(usage) if !self.is_open() => format!(
+                " ({:+.1} MiB, peak {:.1} MiB)",
+                (usage.allocated_on_exit as f64 - usage.allocated_on_enter as f64) / MIB,
+                usage.peak_allocated as f64 / MIB
+            ),
+            _ => String::new(),
+        };
         log!(
             self.level,
-            "{}{:.4}s to {}",
+            "{}{:.4}s to {}{}",
             prefix,
             self.duration().as_secs_f64(),
-            self.name
+            self.name,
+            memory
         );
         for child in &self.children {
             child.print_helper(depth + 1);
@@ -174,6 +350,14 @@ impl TimingTree {
     }
 }
 
+#[cfg(feature = "timing")]
+const MIB: f64 = (1 << 20) as f64;
+
+#[cfg(feature = "timing")]
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
 /// Creates a named scope; useful for debugging.
 #[macro_export]
 macro_rules! timed {
@@ -191,3 +375,37 @@ macro_rules! timed {
         res
     }};
 }
+
+#[cfg(all(test, feature = "timing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_tree_export() {
+        let mut timing = TimingTree::new("prove", Level::Debug);
+        timed!(timing, "commit", {
+            timed!(timing, "lde", ());
+        });
+        timed!(timing, "quotient", ());
+        timing.pop();
+
+        let spans = timing.spans();
+        assert_eq!(spans.name, "prove");
+        assert_eq!(spans.start_us, 0.0);
+        assert_eq!(spans.children.len(), 2);
+        assert_eq!(spans.children[0].children[0].name, "lde");
+        assert!(spans.children[1].start_us >= spans.children[0].start_us);
+
+        let trace = timing.chrome_trace();
+        let names = trace
+            .trace_events
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["prove", "commit", "lde", "quotient"]);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["traceEvents"][1]["ph"], "X");
+        assert_eq!(json["traceEvents"][1]["name"], "commit");
+    }
+}
@@ -0,0 +1,310 @@
+//! Export of the AIR of a [`Stark`] to a self-describing format.
+//!
+//! [`export_air`] outputs an [`AirExport`], which can be serialized with any `serde` backend
+//! (typically JSON) for consumption by external tools. The format is as follows:
+//!
+//! - Field elements are given by their canonical `u64` representation. Elements of the extension
+//!   field are given as the list of their coefficients over the base field.
+//! - `nodes` holds the expressions of all constraints as a DAG, so that shared subexpressions
+//!   are only exported once. Each node is an object with an `op` field, which is one of:
+//!   - `local`, with a `column` field: the value of a column in the current row;
+//!   - `next`, with a `column` field: the value of a column in the next row;
+//!   - `public_input`, with an `index` field;
+//!   - `constant`, with a `value` field;
+//!   - `opaque`: a value not expressible in terms of the above, e.g. an inverse hinted by a
+//!     witness generator;
+//!   - `arithmetic`, with fields `const_0`, `const_1`, `multiplicand_0`, `multiplicand_1` and
+//!     `addend`: the value `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend`, where
+//!     the operands are indices of earlier nodes.
+//! - `constraints` lists, for each constraint, the index of the node computing it and the
+//!   filter it is multiplied by. The constraint enforces that the product vanishes on all rows.
+//! - `lookups` lists the lookups of the STARK, as returned by [`Stark::lookups`]. Columns are
+//!   linear combinations of the current and next rows, as in [`Column`]. The looking columns and
+//!   the table column are tuples of such columns, combined with a challenge by the prover.
+//!
+//! For audits, [`render_air`] instead renders each constraint as an expanded polynomial over
+//! named columns, in a [`ConstraintReport`].
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::any::type_name;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::types::PrimeField64;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::symbolic::{ConstraintReport, SymbolicNode};
+use serde::Serialize;
+
+use crate::constraint_consumer::ConstraintFilter;
+use crate::constraint_profile::symbolic_constraints;
+use crate::lookup::{Column, Filter, Lookup};
+use crate::stark::Stark;
+
+/// A node of the expression DAG of an [`AirExport`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum AirNode {
+    /// The value of a column in the current row.
+    Local {
+        /// The index of the column.
+        column: usize,
+    },
+    /// The value of a column in the next row.
+    Next {
+        /// The index of the column.
+        column: usize,
+    },
+    /// A public input.
+    PublicInput {
+        /// The index of the public input.
+        index: usize,
+    },
+    /// A constant of the extension field.
+    Constant {
+        /// The coefficients of the constant over the base field.
+        value: Vec<u64>,
+    },
+    /// A value which is not an arithmetic expression of the other nodes.
+    Opaque,
+    /// `const_0 * multiplicand_0 * multiplicand_1 + const_1 * addend`.
+    Arithmetic {
+        /// The coefficient of the product.
+        const_0: u64,
+        /// The coefficient of the addend.
+        const_1: u64,
+        /// The index of the node of the first multiplicand.
+        multiplicand_0: usize,
+        /// The index of the node of the second multiplicand.
+        multiplicand_1: usize,
+        /// The index of the node of the addend.
+        addend: usize,
+    },
+}
+
+/// A constraint of an [`AirExport`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AirConstraint {
+    /// The filter this constraint is multiplied by.
+    pub filter: ConstraintFilter,
+    /// The index of the node computing the unfiltered constraint.
+    pub node: usize,
+}
+
+/// A linear combination of the columns of the current and next rows, as in [`Column`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AirColumn {
+    /// Pairs of column indices and coefficients for the current row.
+    pub linear_combination: Vec<(usize, u64)>,
+    /// Pairs of column indices and coefficients for the next row.
+    pub next_row_linear_combination: Vec<(usize, u64)>,
+    /// The constant term.
+    pub constant: u64,
+}
+
+/// A degree 2 combination of columns, as in [`Filter`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AirFilter {
+    /// The degree 2 terms.
+    pub products: Vec<(AirColumn, AirColumn)>,
+    /// The degree 1 terms.
+    pub constants: Vec<AirColumn>,
+}
+
+/// A lookup of an [`AirExport`], as in [`Lookup`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AirLookup {
+    /// Columns whose values should be contained in the lookup table, each a tuple of columns.
+    pub columns: Vec<Vec<AirColumn>>,
+    /// Column containing the lookup table, as a tuple of columns.
+    pub table_column: Vec<AirColumn>,
+    /// Column containing the frequencies of `columns` in `table_column`.
+    pub frequencies_column: AirColumn,
+    /// Filters of the looking columns.
+    pub filter_columns: Vec<AirFilter>,
+}
+
+/// The AIR of a [`Stark`], as output by [`export_air`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AirExport {
+    /// The name of the exported STARK type.
+    pub name: String,
+    /// The order of the base field.
+    pub field_order: u64,
+    /// The degree of the extension field constants are taken from.
+    pub extension_degree: usize,
+    /// The number of columns of the trace.
+    pub num_columns: usize,
+    /// The number of public inputs.
+    pub num_public_inputs: usize,
+    /// The constraint degree declared by [`Stark::constraint_degree`].
+    pub constraint_degree: usize,
+    /// The expression DAG of the constraints, topologically sorted.
+    pub nodes: Vec<AirNode>,
+    /// The constraints, in the order they are emitted.
+    pub constraints: Vec<AirConstraint>,
+    /// The lookups of the STARK.
+    pub lookups: Vec<AirLookup>,
+}
+
+impl<F: PrimeField64> From<&Column<F>> for AirColumn {
+    fn from(column: &Column<F>) -> Self {
+        let export =
+            |lc: &[(usize, F)]| lc.iter().map(|&(c, f)| (c, f.to_canonical_u64())).collect();
+        Self {
+            linear_combination: export(&column.linear_combination),
+            next_row_linear_combination: export(&column.next_row_linear_combination),
+            constant: column.constant.to_canonical_u64(),
+        }
+    }
+}
+
+impl<F: PrimeField64> From<&Filter<F>> for AirFilter {
+    fn from(filter: &Filter<F>) -> Self {
+        Self {
+            products: filter
+                .products
+                .iter()
+                .map(|(a, b)| (a.into(), b.into()))
+                .collect(),
+            constants: filter.constants.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField64> From<&Lookup<F>> for AirLookup {
+    fn from(lookup: &Lookup<F>) -> Self {
+        Self {
+            columns: lookup
+                .columns
+                .iter()
+                .map(|cols| cols.iter().map(Into::into).collect())
+                .collect(),
+            table_column: lookup.table_column.iter().map(Into::into).collect(),
+            frequencies_column: (&lookup.frequencies_column).into(),
+            filter_columns: lookup.filter_columns.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Exports the constraints and lookups of the given STARK.
+///
+/// The constraints are recovered by symbolically evaluating [`Stark::eval_ext_circuit`].
+/// Cross-table lookups are not part of a single STARK and are hence not exported.
+pub fn export_air<F, S, const D: usize>(stark: &S) -> AirExport
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let (filters, dag) = symbolic_constraints(stark);
+
+    let nodes = dag
+        .nodes()
+        .iter()
+        .map(|node| match *node {
+            SymbolicNode::Input(i) if i < S::COLUMNS => AirNode::Local { column: i },
+            SymbolicNode::Input(i) if i < 2 * S::COLUMNS => AirNode::Next {
+                column: i - S::COLUMNS,
+            },
+            SymbolicNode::Input(i) => AirNode::PublicInput {
+                index: i - 2 * S::COLUMNS,
+            },
+            SymbolicNode::Constant(c) => AirNode::Constant {
+                value: c
+                    .to_basefield_array()
+                    .iter()
+                    .map(|f| f.to_canonical_u64())
+                    .collect(),
+            },
+            SymbolicNode::Opaque(_) => AirNode::Opaque,
+            SymbolicNode::Arithmetic {
+                const_0,
+                const_1,
+                multiplicand_0,
+                multiplicand_1,
+                addend,
+            } => AirNode::Arithmetic {
+                const_0: const_0.to_canonical_u64(),
+                const_1: const_1.to_canonical_u64(),
+                multiplicand_0,
+                multiplicand_1,
+                addend,
+            },
+        })
+        .collect();
+
+    let constraints = filters
+        .into_iter()
+        .zip(dag.outputs())
+        .map(|(filter, &node)| AirConstraint { filter, node })
+        .collect();
+
+    AirExport {
+        name: type_name::<S>().to_string(),
+        field_order: F::ORDER,
+        extension_degree: D,
+        num_columns: S::COLUMNS,
+        num_public_inputs: S::PUBLIC_INPUTS,
+        constraint_degree: stark.constraint_degree(),
+        nodes,
+        constraints,
+        lookups: stark.lookups().iter().map(Into::into).collect(),
+    }
+}
+
+/// Renders the constraints of the given STARK as expanded polynomials over its columns and public
+/// inputs, recovered by symbolically evaluating [`Stark::eval_ext_circuit`].
+///
+/// Columns of the current row are named after `column_names`, or `col[i]` past its end, and the
+/// same columns of the next row are suffixed with `'`; public inputs are named `pi[i]`. Degrees
+/// include the first or last row filter of a constraint, as in
+/// [`profile_stark_constraints`](crate::constraint_profile::profile_stark_constraints).
+/// Constraints with more than `max_terms` terms are not rendered.
+pub fn render_air<F, S, const D: usize>(
+    stark: &S,
+    column_names: &[&str],
+    max_terms: usize,
+) -> ConstraintReport
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let (filters, dag) = symbolic_constraints(stark);
+
+    let columns = (0..S::COLUMNS)
+        .map(|i| match column_names.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("col[{}]", i),
+        })
+        .collect::<Vec<_>>();
+    let variables = columns
+        .iter()
+        .cloned()
+        .chain(columns.iter().map(|name| format!("{}'", name)))
+        .chain((0..S::PUBLIC_INPUTS).map(|i| format!("pi[{}]", i)))
+        .collect();
+    let input_degrees = [vec![1; 2 * S::COLUMNS], vec![0; S::PUBLIC_INPUTS]].concat();
+
+    let mut report = ConstraintReport::new(
+        type_name::<S>().to_string(),
+        variables,
+        &dag,
+        &input_degrees,
+        max_terms,
+    );
+    for (constraint, filter) in report.constraints.iter_mut().zip(filters) {
+        let (name, extra_degree) = match filter {
+            ConstraintFilter::AllRows => ("all_rows", 0),
+            ConstraintFilter::Transition => ("transition", 0),
+            ConstraintFilter::FirstRow => ("first_row", 1),
+            ConstraintFilter::LastRow => ("last_row", 1),
+        };
+        constraint.filter = Some(name.to_string());
+        constraint.degree = constraint.degree.map(|d| d + extra_degree);
+    }
+    report
+}
@@ -7,13 +7,15 @@
 #[cfg(not(feature = "std"))]
 use alloc::format;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
 use plonky2::fri::reduction_strategies::FriReductionStrategy;
 use plonky2::fri::{FriConfig, FriParams};
 use plonky2::hash::hash_types::RichField;
 
+use crate::stark::Stark;
+
 /// A configuration containing the different parameters used by the STARK prover.
 #[derive(Clone, Debug)]
 pub struct StarkConfig {
@@ -94,6 +96,46 @@ impl StarkConfig {
             Ok(())
         }
     }
+
+    /// Outputs the maximum constraint degree supported by this configuration.
+    ///
+    /// The quotient polynomials of a STARK with constraint degree `d` have degree
+    /// `(d - 1) * n` and are computed over an LDE of the trace, hence `d` must not
+    /// exceed the blowup factor plus one.
+    pub const fn max_constraint_degree(&self) -> usize {
+        (1 << self.fri_config.rate_bits) + 1
+    }
+
+    /// Checks that the constraint degree declared by the provided [`Stark`] is
+    /// supported by this STARK configuration.
+    pub fn check_constraint_degree<F, S, const D: usize>(&self, stark: &S) -> Result<()>
+    where
+        F: RichField + Extendable<D>,
+        S: Stark<F, D>,
+    {
+        let constraint_degree = stark.constraint_degree();
+
+        ensure!(
+            constraint_degree <= self.max_constraint_degree(),
+            "Constraint degree {} exceeds the maximum degree {} supported with rate_bits = {}",
+            constraint_degree,
+            self.max_constraint_degree(),
+            self.fri_config.rate_bits
+        );
+        // Lookup helper columns batch `constraint_degree - 1` columns together.
+        ensure!(
+            constraint_degree != 1 || !stark.uses_lookups(),
+            "Lookups are not supported with a constraint degree of 1"
+        );
+        // Cross-table lookup helper columns batch `constraint_degree - 1` columns together.
+        ensure!(
+            constraint_degree >= 2 || !stark.requires_ctls(),
+            "Cross-table lookups require a constraint degree of at least 2, got {}",
+            constraint_degree
+        );
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -144,4 +186,23 @@ mod tests {
         // bits of security for FRI, which falls short of the 100 bits of security target.
         assert!(too_few_queries_config.check_config::<F, D>().is_err());
     }
+
+    #[test]
+    fn test_max_constraint_degree() {
+        let config = StarkConfig::standard_fast_config();
+        assert_eq!(config.max_constraint_degree(), 3);
+
+        let high_rate_config = StarkConfig::new(
+            100,
+            2,
+            FriConfig {
+                rate_bits: 3,
+                cap_height: 4,
+                proof_of_work_bits: 16,
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+                num_query_rounds: 28,
+            },
+        );
+        assert_eq!(high_rate_config.max_constraint_degree(), 9);
+    }
 }
@@ -13,6 +13,21 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use serde::Serialize;
+
+/// The filter applied to a constraint, i.e. the set of rows on which it must hold.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintFilter {
+    /// The constraint holds on all rows.
+    AllRows,
+    /// The constraint holds on all rows except the last.
+    Transition,
+    /// The constraint holds on the first row only.
+    FirstRow,
+    /// The constraint holds on the last row only.
+    LastRow,
+}
 
 /// A [`ConstraintConsumer`] evaluates all constraint, permutation and cross-table
 /// lookup polynomials of a [`Stark`][crate::stark::Stark].
@@ -105,6 +120,9 @@ pub struct RecursiveConstraintConsumer<F: RichField + Extendable<D>, const D: us
     /// with the last trace row, and zero at other points in the subgroup.
     lagrange_basis_last: ExtensionTarget<D>,
 
+    /// All constraints that have been emitted so far, before filtering.
+    constraints: Vec<(ConstraintFilter, ExtensionTarget<D>)>,
+
     _phantom: PhantomData<F>,
 }
 
@@ -123,6 +141,7 @@ impl<F: RichField + Extendable<D>, const D: usize> RecursiveConstraintConsumer<F
             z_last,
             lagrange_basis_first,
             lagrange_basis_last,
+            constraints: Vec::new(),
             _phantom: Default::default(),
         }
     }
@@ -133,14 +152,22 @@ impl<F: RichField + Extendable<D>, const D: usize> RecursiveConstraintConsumer<F
         self.constraint_accs
     }
 
+    /// Outputs all the constraints emitted so far, in order, along with the filter applied to
+    /// each of them. The constraints are given before being multiplied by their filter.
+    pub fn constraints(&self) -> &[(ConstraintFilter, ExtensionTarget<D>)] {
+        &self.constraints
+    }
+
     /// Add one constraint valid on all rows except the last.
     pub fn constraint_transition(
         &mut self,
         builder: &mut CircuitBuilder<F, D>,
         constraint: ExtensionTarget<D>,
     ) {
+        self.constraints
+            .push((ConstraintFilter::Transition, constraint));
         let filtered_constraint = builder.mul_extension(constraint, self.z_last);
-        self.constraint(builder, filtered_constraint);
+        self.accumulate(builder, filtered_constraint);
     }
 
     /// Add one constraint valid on all rows.
@@ -149,9 +176,9 @@ impl<F: RichField + Extendable<D>, const D: usize> RecursiveConstraintConsumer<F
         builder: &mut CircuitBuilder<F, D>,
         constraint: ExtensionTarget<D>,
     ) {
-        for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
-            *acc = builder.scalar_mul_add_extension(alpha, *acc, constraint);
-        }
+        self.constraints
+            .push((ConstraintFilter::AllRows, constraint));
+        self.accumulate(builder, constraint);
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
@@ -161,8 +188,10 @@ impl<F: RichField + Extendable<D>, const D: usize> RecursiveConstraintConsumer<F
         builder: &mut CircuitBuilder<F, D>,
         constraint: ExtensionTarget<D>,
     ) {
+        self.constraints
+            .push((ConstraintFilter::FirstRow, constraint));
         let filtered_constraint = builder.mul_extension(constraint, self.lagrange_basis_first);
-        self.constraint(builder, filtered_constraint);
+        self.accumulate(builder, filtered_constraint);
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
@@ -172,7 +201,16 @@ impl<F: RichField + Extendable<D>, const D: usize> RecursiveConstraintConsumer<F
         builder: &mut CircuitBuilder<F, D>,
         constraint: ExtensionTarget<D>,
     ) {
+        self.constraints
+            .push((ConstraintFilter::LastRow, constraint));
         let filtered_constraint = builder.mul_extension(constraint, self.lagrange_basis_last);
-        self.constraint(builder, filtered_constraint);
+        self.accumulate(builder, filtered_constraint);
+    }
+
+    /// Adds an already filtered constraint to the running sums.
+    fn accumulate(&mut self, builder: &mut CircuitBuilder<F, D>, constraint: ExtensionTarget<D>) {
+        for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
+            *acc = builder.scalar_mul_add_extension(alpha, *acc, constraint);
+        }
     }
 }
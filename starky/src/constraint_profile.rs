@@ -0,0 +1,177 @@
+//! Static analysis of the constraints of a [`Stark`].
+//!
+//! The constraints are evaluated symbolically, by running [`Stark::eval_ext_circuit`] on
+//! fresh targets and recovering the arithmetic operations it performs. This yields, for
+//! each constraint, its degree, the columns and public inputs it involves, and the filter
+//! gating it. The resulting [`StarkConstraintProfile`] can be serialized, e.g. to JSON, for
+//! external review.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::any::type_name;
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::util::symbolic::SymbolicDag;
+use serde::Serialize;
+
+use crate::constraint_consumer::{ConstraintFilter, RecursiveConstraintConsumer};
+use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::stark::Stark;
+
+/// The profile of a single constraint of a [`Stark`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ConstraintProfile {
+    /// The filter gating this constraint.
+    pub filter: ConstraintFilter,
+    /// The degree of this constraint, including its first or last row filter. This is `None`
+    /// if the constraint depends on values which are not arithmetic expressions of the trace.
+    pub degree: Option<usize>,
+    /// The columns of the current row involved in this constraint.
+    pub local_columns: Vec<usize>,
+    /// The columns of the next row involved in this constraint.
+    pub next_columns: Vec<usize>,
+    /// The public inputs involved in this constraint.
+    pub public_inputs: Vec<usize>,
+}
+
+/// The profile of all the constraints of a [`Stark`], as output by
+/// [`profile_stark_constraints`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct StarkConstraintProfile {
+    /// The name of the profiled STARK type.
+    pub name: String,
+    /// The number of columns of the trace.
+    pub num_columns: usize,
+    /// The number of public inputs.
+    pub num_public_inputs: usize,
+    /// The constraint degree declared by [`Stark::constraint_degree`].
+    pub constraint_degree: usize,
+    /// The profile of each constraint, in the order they are emitted.
+    pub constraints: Vec<ConstraintProfile>,
+    /// The columns which are not involved in any constraint.
+    pub unconstrained_columns: Vec<usize>,
+}
+
+impl StarkConstraintProfile {
+    /// Outputs the maximal degree among all constraints, or `None` if the degree of some
+    /// constraint could not be determined.
+    pub fn max_degree(&self) -> Option<usize> {
+        self.constraints
+            .iter()
+            .try_fold(0, |max, c| c.degree.map(|d| max.max(d)))
+    }
+
+    /// Outputs the indices of the constraints whose degree exceeds the declared
+    /// [`Stark::constraint_degree`].
+    pub fn constraints_exceeding_degree(&self) -> Vec<usize> {
+        self.constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.degree.is_some_and(|d| d > self.constraint_degree))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Symbolically evaluates the constraints of the given STARK.
+///
+/// Outputs the filter of each constraint, along with a [`SymbolicDag`] whose outputs are the
+/// unfiltered constraints and whose inputs are the local values, the next values and the public
+/// inputs, in this order.
+pub(crate) fn symbolic_constraints<F, S, const D: usize>(
+    stark: &S,
+) -> (Vec<ConstraintFilter>, SymbolicDag<F, D>)
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+
+    let local_values = builder.add_virtual_extension_targets(S::COLUMNS);
+    let next_values = builder.add_virtual_extension_targets(S::COLUMNS);
+    let public_inputs = builder.add_virtual_extension_targets(S::PUBLIC_INPUTS);
+    let vars = S::EvaluationFrameTarget::from_values(&local_values, &next_values, &public_inputs);
+
+    let alphas = builder.add_virtual_targets(1);
+    let z_last = builder.add_virtual_extension_target();
+    let lagrange_first = builder.add_virtual_extension_target();
+    let lagrange_last = builder.add_virtual_extension_target();
+    let mut consumer = RecursiveConstraintConsumer::<F, D>::new(
+        builder.zero_extension(),
+        alphas,
+        z_last,
+        lagrange_first,
+        lagrange_last,
+    );
+    stark.eval_ext_circuit(&mut builder, &vars, &mut consumer);
+
+    let (filters, constraints): (Vec<_>, Vec<_>) = consumer.constraints().iter().copied().unzip();
+    let inputs = [local_values, next_values, public_inputs].concat();
+    let dag = builder.symbolic_dag(&inputs, &constraints);
+
+    (filters, dag)
+}
+
+/// Symbolically evaluates the constraints of the given STARK and profiles each of them.
+///
+/// **Note**: only the constraints of [`Stark::eval_ext_circuit`] are profiled, not the
+/// lookups or cross-table lookups the STARK may be involved in.
+pub fn profile_stark_constraints<F, S, const D: usize>(stark: &S) -> StarkConstraintProfile
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let (filters, dag) = symbolic_constraints(stark);
+
+    // Trace values have degree 1, public inputs are constants.
+    let input_degrees = [vec![1; 2 * S::COLUMNS], vec![0; S::PUBLIC_INPUTS]].concat();
+    let degrees = dag.degrees(&input_degrees);
+
+    let mut is_constrained = vec![false; S::COLUMNS];
+    let constraints = filters
+        .into_iter()
+        .zip(dag.outputs())
+        .map(|(filter, &node)| {
+            let mut profile = ConstraintProfile {
+                filter,
+                // The Lagrange selectors have the same degree as a trace column,
+                // while `X - g^(n-1)` does not increase the degree in a meaningful way.
+                degree: degrees[node].map(|d| match filter {
+                    ConstraintFilter::FirstRow | ConstraintFilter::LastRow => d + 1,
+                    ConstraintFilter::AllRows | ConstraintFilter::Transition => d,
+                }),
+                local_columns: vec![],
+                next_columns: vec![],
+                public_inputs: vec![],
+            };
+            for input in dag.input_dependencies(node) {
+                if input < S::COLUMNS {
+                    profile.local_columns.push(input);
+                    is_constrained[input] = true;
+                } else if input < 2 * S::COLUMNS {
+                    profile.next_columns.push(input - S::COLUMNS);
+                    is_constrained[input - S::COLUMNS] = true;
+                } else {
+                    profile.public_inputs.push(input - 2 * S::COLUMNS);
+                }
+            }
+            profile
+        })
+        .collect();
+
+    StarkConstraintProfile {
+        name: type_name::<S>().to_string(),
+        num_columns: S::COLUMNS,
+        num_public_inputs: S::PUBLIC_INPUTS,
+        constraint_degree: stark.constraint_degree(),
+        constraints,
+        unconstrained_columns: (0..S::COLUMNS).filter(|&c| !is_constrained[c]).collect(),
+    }
+}
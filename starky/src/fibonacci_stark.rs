@@ -140,7 +140,7 @@ mod tests {
     use anyhow::Result;
     use itertools::Itertools;
     use plonky2::field::extension::Extendable;
-    use plonky2::field::types::Field;
+    use plonky2::field::types::{Field, Field64};
     use plonky2::hash::hash_types::RichField;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
@@ -148,16 +148,22 @@ mod tests {
     use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
     use plonky2::util::timing::TimingTree;
 
+    use crate::air_export::{export_air, render_air, AirNode};
     use crate::config::StarkConfig;
+    use crate::constraint_consumer::ConstraintFilter;
+    use crate::constraint_profile::profile_stark_constraints;
     use crate::fibonacci_stark::FibonacciStark;
     use crate::proof::StarkProofWithPublicInputs;
     use crate::prover::prove;
     use crate::recursive_verifier::{
         add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
-        verify_stark_proof_circuit,
+        verify_stark_proof_circuit, StarkProofWrapper,
     };
     use crate::stark::Stark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::stark_testing::{
+        test_stark_circuit_constraints, test_stark_constraint_degrees, test_stark_low_degree,
+        test_stark_mutations, TraceMutation,
+    };
     use crate::verifier::verify_stark_proof;
 
     const D: usize = 2;
@@ -189,10 +195,37 @@ mod tests {
         verify_stark_proof(stark, proof, &config, None)
     }
 
+    #[cfg(all(feature = "mmap", unix))]
+    #[test]
+    fn test_fibonacci_stark_with_storage() -> Result<()> {
+        use plonky2::util::column_storage::ColumnStorage;
+
+        use crate::prover::prove_with_storage;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove_with_storage::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &ColumnStorage::Mapped(std::env::temp_dir()),
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config, None)
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         let num_rows = 1 << 5;
         let stark = S::new(num_rows);
+        test_stark_constraint_degrees(stark)?;
         test_stark_low_degree(stark)
     }
 
@@ -203,6 +236,120 @@ mod tests {
         test_stark_circuit_constraints::<F, C, S, D>(stark)
     }
 
+    #[test]
+    fn test_fibonacci_stark_mutations() -> Result<()> {
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+
+        let mutations = [
+            TraceMutation::all_cells(num_rows, &[0, 1]),
+            TraceMutation::adjacent_row_swaps(num_rows),
+        ]
+        .concat();
+        test_stark_mutations(&stark, &trace, &public_inputs, &mutations)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_constraint_profile() -> Result<()> {
+        let stark = S::new(1 << 5);
+        let profile = profile_stark_constraints(&stark);
+
+        let filters = profile.constraints.iter().map(|c| c.filter).collect_vec();
+        assert_eq!(
+            filters,
+            [
+                ConstraintFilter::FirstRow,
+                ConstraintFilter::FirstRow,
+                ConstraintFilter::LastRow,
+                ConstraintFilter::Transition,
+                ConstraintFilter::Transition,
+            ]
+        );
+        let degrees = profile.constraints.iter().map(|c| c.degree).collect_vec();
+        assert_eq!(degrees, [Some(2), Some(2), Some(2), Some(1), Some(1)]);
+        assert_eq!(profile.max_degree(), Some(stark.constraint_degree()));
+        assert!(profile.constraints_exceeding_degree().is_empty());
+        assert!(profile.unconstrained_columns.is_empty());
+
+        // x1' <- x0 + x1
+        let constraint = &profile.constraints[4];
+        assert_eq!(constraint.local_columns, [0, 1]);
+        assert_eq!(constraint.next_columns, [1]);
+        assert!(constraint.public_inputs.is_empty());
+        assert_eq!(profile.constraints[2].public_inputs, [S::PI_INDEX_RES]);
+
+        let json = serde_json::to_value(&profile)?;
+        assert_eq!(json["constraints"][2]["filter"], "last_row");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_air_export() -> Result<()> {
+        let num_rows = 1 << 5;
+        let stark = S::new(num_rows);
+        let air = export_air(&stark);
+        assert_eq!(air.constraints.len(), 5);
+        assert!(air.lookups.is_empty());
+
+        // Evaluating the exported constraints on a valid transition should yield zero.
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let mut values: Vec<F> = Vec::with_capacity(air.nodes.len());
+        for node in &air.nodes {
+            let value = match *node {
+                AirNode::Local { column } => trace[column].values[3],
+                AirNode::Next { column } => trace[column].values[4],
+                AirNode::PublicInput { index } => public_inputs[index],
+                AirNode::Constant { ref value } => F::from_canonical_u64(value[0]),
+                AirNode::Opaque => unreachable!("Fibonacci constraints are arithmetic"),
+                AirNode::Arithmetic {
+                    const_0,
+                    const_1,
+                    multiplicand_0,
+                    multiplicand_1,
+                    addend,
+                } => {
+                    F::from_canonical_u64(const_0) * values[multiplicand_0] * values[multiplicand_1]
+                        + F::from_canonical_u64(const_1) * values[addend]
+                }
+            };
+            values.push(value);
+        }
+        for constraint in &air.constraints[3..] {
+            assert_eq!(values[constraint.node], F::ZERO);
+        }
+
+        let json = serde_json::to_value(&air)?;
+        assert_eq!(json["constraints"][0]["filter"], "first_row");
+        assert_eq!(json["field_order"], F::ORDER);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_render_air() -> Result<()> {
+        let stark = S::new(1 << 5);
+        let report = render_air(&stark, &["x0", "x1"], 100);
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "# {}\n\
+                 constraint 0 [first_row] (degree 2): x0 - pi[0] = 0\n\
+                 constraint 1 [first_row] (degree 2): x1 - pi[1] = 0\n\
+                 constraint 2 [last_row] (degree 2): x1 - pi[2] = 0\n\
+                 constraint 3 [transition] (degree 1): -x1 + x0' = 0\n\
+                 constraint 4 [transition] (degree 1): -x0 - x1 + x1' = 0\n",
+                core::any::type_name::<S>()
+            )
+        );
+
+        let json = serde_json::to_value(&report)?;
+        assert_eq!(json["variables"][3], "x1'");
+        assert_eq!(json["constraints"][4]["polynomial"], "-x0 - x1 + x1'");
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_stark_verifier() -> Result<()> {
         init_logger();
@@ -229,6 +376,39 @@ mod tests {
         recursive_proof::<F, C, S, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_stark_proof_wrapper() -> Result<()> {
+        init_logger();
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let stark = S::new(num_rows);
+        let mut wrapper = StarkProofWrapper::<F, C, S, C, D>::new(
+            stark,
+            &config,
+            CircuitConfig::standard_recursion_config(),
+        );
+
+        // The second proof reuses the circuit built for the first one.
+        for x1 in [F::ONE, F::TWO] {
+            let public_inputs = [F::ZERO, x1, fibonacci(num_rows - 1, F::ZERO, x1)];
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                None,
+                &mut TimingTree::default(),
+            )?;
+
+            let wrapped_proof = wrapper.wrap(&proof)?;
+            assert_eq!(wrapped_proof.public_inputs, public_inputs);
+            wrapper.circuit_data(5).verify(wrapped_proof)?;
+        }
+        Ok(())
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
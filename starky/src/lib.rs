@@ -215,8 +215,10 @@ extern crate alloc;
 
 mod get_challenges;
 
+pub mod air_export;
 pub mod config;
 pub mod constraint_consumer;
+pub mod constraint_profile;
 pub mod cross_table_lookup;
 pub mod evaluation_frame;
 pub mod lookup;
@@ -35,8 +35,8 @@ use crate::stark::Stark;
 /// the degree 1 terms.
 #[derive(Clone, Debug)]
 pub struct Filter<F: Field> {
-    products: Vec<(Column<F>, Column<F>)>,
-    constants: Vec<Column<F>>,
+    pub(crate) products: Vec<(Column<F>, Column<F>)>,
+    pub(crate) constants: Vec<Column<F>>,
 }
 
 /// The default filter is always on.
@@ -135,9 +135,9 @@ impl<F: Field> Filter<F> {
 /// - the constant of the linear combination.
 #[derive(Clone, Debug)]
 pub struct Column<F: Field> {
-    linear_combination: Vec<(usize, F)>,
-    next_row_linear_combination: Vec<(usize, F)>,
-    constant: F,
+    pub(crate) linear_combination: Vec<(usize, F)>,
+    pub(crate) next_row_linear_combination: Vec<(usize, F)>,
+    pub(crate) constant: F,
 }
 
 impl<F: Field> Column<F> {
@@ -405,6 +405,10 @@ pub(crate) type ColumnFilter<'a, F> = (&'a [Column<F>], &'a Filter<F>);
 /// along with a `frequencies_column` indicating the frequency of each looking
 /// column in the looked table.
 ///
+/// Each looking column, as well as the table column, is a tuple of [`Column`]s, whose values
+/// `c_i` in a row are combined as `Σ γ^i·c_i` with a challenge `γ` before the logUp step. Most
+/// lookups use tuples of a single [`Column`].
+///
 /// It also features a `filter_columns` vector, optionally adding at most one
 /// filter per looking column.
 ///
@@ -415,10 +419,10 @@ pub(crate) type ColumnFilter<'a, F> = (&'a [Column<F>], &'a Filter<F>);
 pub struct Lookup<F: Field> {
     /// Columns whose values should be contained in the lookup table.
     /// These are the f_i(x) polynomials in the logUp paper.
-    pub columns: Vec<Column<F>>,
+    pub columns: Vec<Vec<Column<F>>>,
     /// Column containing the lookup table.
     /// This is the t(x) polynomial in the logUp paper.
-    pub table_column: Column<F>,
+    pub table_column: Vec<Column<F>>,
     /// Column containing the frequencies of `columns` in `table_column`.
     /// This is the m(x) polynomial in the paper.
     pub frequencies_column: Column<F>,
@@ -429,6 +433,29 @@ pub struct Lookup<F: Field> {
 }
 
 impl<F: Field> Lookup<F> {
+    /// Returns a [`Lookup`] enforcing that the rows of `permuted` are a permutation of the rows of
+    /// `columns`, i.e. that both tuples of columns hold the same multiset of rows over the trace.
+    ///
+    /// This is a logUp argument where every row of `permuted` is looked up exactly once,
+    /// so no frequencies column needs to be committed.
+    pub fn permutation(columns: Vec<Column<F>>, permuted: Vec<Column<F>>) -> Self {
+        assert!(
+            !columns.is_empty(),
+            "A permutation needs at least one column"
+        );
+        assert_eq!(
+            columns.len(),
+            permuted.len(),
+            "Both sides of a permutation must have the same number of columns"
+        );
+        Self {
+            columns: vec![columns],
+            table_column: permuted,
+            frequencies_column: Column::one(),
+            filter_columns: vec![Filter::default()],
+        }
+    }
+
     /// Outputs the number of helper columns needed by this [`Lookup`].
     pub fn num_helper_columns(&self, constraint_degree: usize) -> usize {
         // One helper column for each column batch of size `constraint_degree-1`,
@@ -576,10 +603,12 @@ pub fn get_grand_product_challenge_set_target<
 /// Given columns `f0,...,fk` and a column `t`, such that `∪fi ⊆ t`, and challenges `x`,
 /// this computes the helper columns `h_i = 1/(x+f_2i) + 1/(x+f_2i+1)`, `g = 1/(x+t)`,
 /// and `Z(gx) = Z(x) + sum h_i(x) - m(x)g(x)` where `m` is the frequencies column.
+/// The tuples of columns making up each `fi` and `t` are first combined with the `beta` of
+/// `challenge`, while `x` is its `gamma`.
 pub(crate) fn lookup_helper_columns<F: Field>(
     lookup: &Lookup<F>,
     trace_poly_values: &[PolynomialValues<F>],
-    challenge: F,
+    challenge: GrandProductChallenge<F>,
     constraint_degree: usize,
 ) -> Vec<PolynomialValues<F>> {
     assert_eq!(lookup.columns.len(), lookup.filter_columns.len());
@@ -589,18 +618,8 @@ pub(crate) fn lookup_helper_columns<F: Field>(
 
     let num_helper_columns = lookup.num_helper_columns(constraint_degree);
 
-    let looking_cols = lookup
+    let columns_filters = lookup
         .columns
-        .iter()
-        .map(|col| vec![col.clone()])
-        .collect::<Vec<Vec<Column<F>>>>();
-
-    let grand_challenge = GrandProductChallenge {
-        beta: F::ONE,
-        gamma: challenge,
-    };
-
-    let columns_filters = looking_cols
         .iter()
         .zip(lookup.filter_columns.iter())
         .map(|(col, filter)| (&col[..], filter))
@@ -611,24 +630,31 @@ pub(crate) fn lookup_helper_columns<F: Field>(
     //       * Here, the first ratio m_0(x)/phi_0(x) is not included with the columns batched up to create the
     //         h_k polynomials; instead there's a separate helper column for it (see below).
     //       * Here, we use 1 instead of -1 as the numerator (and subtract later).
-    //       * Here, for now, the batch size (l) is always constraint_degree - 1 = 2.
+    //       * Here, the batch size (l) is `constraint_degree - 1`.
     //       * Here, there are filters for the columns, to only select some rows
     //         in a given column.
     let mut helper_columns = get_helper_cols(
         trace_poly_values,
         trace_poly_values[0].len(),
         &columns_filters,
-        grand_challenge,
+        challenge,
         constraint_degree,
     );
 
     // Add `1/(table+challenge)` to the helper columns.
     // This is 1/phi_0(x) = 1/(x + t(x)) from the paper.
     // Here, we don't include m(x) in the numerator, instead multiplying it with this column later.
-    let mut table = lookup.table_column.eval_all_rows(trace_poly_values);
-    for x in table.iter_mut() {
-        *x = challenge + *x;
-    }
+    let table_columns = lookup
+        .table_column
+        .iter()
+        .map(|col| col.eval_all_rows(trace_poly_values))
+        .collect::<Vec<_>>();
+    let table = (0..trace_poly_values[0].len())
+        .map(|i| {
+            let row = table_columns.iter().map(|col| col[i]).collect::<Vec<F>>();
+            challenge.combine(&row)
+        })
+        .collect::<Vec<F>>();
     let table_inverse: Vec<F> = F::batch_multiplicative_inverse(&table);
 
     // Compute the `Z` polynomial with `Z(1)=0` and `Z(gx) = Z(x) + sum h_i(x) - frequencies(x)g(x)`.
@@ -797,7 +823,7 @@ where
 {
     pub(crate) local_values: Vec<P>,
     pub(crate) next_values: Vec<P>,
-    pub(crate) challenges: Vec<F>,
+    pub(crate) challenges: Vec<GrandProductChallenge<F>>,
 }
 
 /// Constraints for the logUp lookup argument.
@@ -819,15 +845,15 @@ pub(crate) fn eval_packed_lookups_generic<F, FE, P, S, const D: usize, const D2:
     let mut start = 0;
     for lookup in lookups {
         let num_helper_columns = lookup.num_helper_columns(degree);
-        for &challenge in &lookup_vars.challenges {
-            let grand_challenge = GrandProductChallenge {
-                beta: F::ONE,
-                gamma: challenge,
-            };
+        for challenge in &lookup_vars.challenges {
             let lookup_columns = lookup
                 .columns
                 .iter()
-                .map(|col| vec![col.eval_with_next(local_values, next_values)])
+                .map(|cols| {
+                    cols.iter()
+                        .map(|col| col.eval_with_next(local_values, next_values))
+                        .collect()
+                })
                 .collect::<Vec<Vec<P>>>();
 
             // For each chunk, check that `h_i (x+f_2i) (x+f_{2i+1}) = (x+f_2i) * filter_{2i+1} + (x+f_{2i+1}) * filter_2i`
@@ -839,16 +865,19 @@ pub(crate) fn eval_packed_lookups_generic<F, FE, P, S, const D: usize, const D2:
                 next_values,
                 &lookup_vars.local_values[start..start + num_helper_columns - 1],
                 degree,
-                &grand_challenge,
+                challenge,
                 yield_constr,
             );
 
-            let challenge = FE::from_basefield(challenge);
-
             // Check the `Z` polynomial.
             let z = lookup_vars.local_values[start + num_helper_columns - 1];
             let next_z = lookup_vars.next_values[start + num_helper_columns - 1];
-            let table_with_challenge = lookup.table_column.eval(local_values) + challenge;
+            let table_values = lookup
+                .table_column
+                .iter()
+                .map(|col| col.eval(local_values))
+                .collect::<Vec<P>>();
+            let table_with_challenge = challenge.combine(&table_values);
             let y = lookup_vars.local_values[start..start + num_helper_columns - 1]
                 .iter()
                 .fold(P::ZEROS, |acc, x| acc + *x)
@@ -866,7 +895,7 @@ pub(crate) fn eval_packed_lookups_generic<F, FE, P, S, const D: usize, const D2:
 pub(crate) struct LookupCheckVarsTarget<const D: usize> {
     pub(crate) local_values: Vec<ExtensionTarget<D>>,
     pub(crate) next_values: Vec<ExtensionTarget<D>>,
-    pub(crate) challenges: Vec<Target>,
+    pub(crate) challenges: Vec<GrandProductChallenge<Target>>,
 }
 
 pub(crate) fn eval_ext_lookups_circuit<
@@ -891,15 +920,19 @@ pub(crate) fn eval_ext_lookups_circuit<
         let col_values = lookup
             .columns
             .iter()
-            .map(|col| vec![col.eval_with_next_circuit(builder, local_values, next_values)])
+            .map(|cols| {
+                cols.iter()
+                    .map(|col| col.eval_with_next_circuit(builder, local_values, next_values))
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let table_values = lookup
+            .table_column
+            .iter()
+            .map(|col| col.eval_circuit(builder, local_values))
             .collect::<Vec<_>>();
 
-        for &challenge in &lookup_vars.challenges {
-            let grand_challenge = GrandProductChallenge {
-                beta: builder.one(),
-                gamma: challenge,
-            };
-
+        for challenge in &lookup_vars.challenges {
             eval_helper_columns_circuit(
                 builder,
                 &lookup.filter_columns,
@@ -908,17 +941,13 @@ pub(crate) fn eval_ext_lookups_circuit<
                 next_values,
                 &lookup_vars.local_values[start..start + num_helper_columns - 1],
                 degree,
-                &grand_challenge,
+                challenge,
                 yield_constr,
             );
-            let challenge = builder.convert_to_ext(challenge);
 
             let z = lookup_vars.local_values[start + num_helper_columns - 1];
             let next_z = lookup_vars.next_values[start + num_helper_columns - 1];
-            let table_column = lookup
-                .table_column
-                .eval_circuit(builder, vars.get_local_values());
-            let table_with_challenge = builder.add_extension(table_column, challenge);
+            let table_with_challenge = challenge.combine_circuit(builder, &table_values);
             let mut y = builder.add_many_extension(
                 &lookup_vars.local_values[start..start + num_helper_columns - 1],
             );
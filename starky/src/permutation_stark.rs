@@ -35,14 +35,13 @@ impl<F: RichField + Extendable<D>, const D: usize> PermutationStark<F, D> {
         }
     }
 
-    /// Generate the trace using `x0, x0+1, 1` as initial state values.
+    /// Generate the trace using `x0, x0+1` as initial state values.
     fn generate_trace(&self, x0: F) -> Vec<PolynomialValues<F>> {
         let mut trace_rows = (0..self.num_rows)
-            .scan([x0, x0 + F::ONE, F::ONE], |acc, _| {
+            .scan([x0, x0 + F::ONE], |acc, _| {
                 let tmp = *acc;
                 acc[0] = tmp[0] + F::ONE;
                 acc[1] = tmp[1] + F::ONE;
-                // acc[2] (i.e. frequency column) remains unchanged, as we're permuting a strictly monotonous sequence.
                 Some(tmp)
             })
             .collect::<Vec<_>>();
@@ -51,7 +50,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PermutationStark<F, D> {
     }
 }
 
-const PERM_COLUMNS: usize = 3;
+const PERM_COLUMNS: usize = 2;
 const PERM_PUBLIC_INPUTS: usize = 1;
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PermutationStark<F, D> {
@@ -69,12 +68,10 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PermutationSt
     }
 
     fn lookups(&self) -> Vec<Lookup<F>> {
-        vec![Lookup {
-            columns: vec![Column::single(0)],
-            table_column: Column::single(1),
-            frequencies_column: Column::single(2),
-            filter_columns: vec![Default::default()],
-        }]
+        vec![Lookup::permutation(
+            vec![Column::single(0)],
+            vec![Column::single(1)],
+        )]
     }
 
     // We don't constrain any register, for the sake of highlighting the permutation argument only.
@@ -100,17 +97,26 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PermutationSt
 
 #[cfg(test)]
 mod tests {
+    use core::marker::PhantomData;
+
     use anyhow::Result;
-    use plonky2::field::extension::Extendable;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::polynomial::PolynomialValues;
     use plonky2::field::types::Field;
     use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::ext_target::ExtensionTarget;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
     use plonky2::util::timing::TimingTree;
 
+    use crate::air_export::export_air;
     use crate::config::StarkConfig;
+    use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use crate::evaluation_frame::StarkFrame;
+    use crate::lookup::{Column, Lookup};
     use crate::permutation_stark::PermutationStark;
     use crate::proof::StarkProofWithPublicInputs;
     use crate::prover::prove;
@@ -119,9 +125,77 @@ mod tests {
         verify_stark_proof_circuit,
     };
     use crate::stark::Stark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::stark_testing::{
+        test_stark_circuit_constraints, test_stark_constraint_degrees, test_stark_low_degree,
+    };
+    use crate::util::trace_rows_to_poly_values;
     use crate::verifier::verify_stark_proof;
 
+    /// A STARK enforcing that the rows of the pair of columns `2, 3` are a permutation of the rows
+    /// of the pair of columns `0, 1`.
+    #[derive(Copy, Clone)]
+    struct PairPermutationStark<F: RichField + Extendable<D>, const D: usize> {
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> PairPermutationStark<F, D> {
+        /// Generates a trace whose columns `0, 1` hold the rows `(i, 2i)`, and whose columns
+        /// `2, 3` hold the same rows shifted by one. If `mismatched`, column `3` is shifted by
+        /// two instead, so that each column is still a permutation of the corresponding one, but
+        /// the pairs are not.
+        fn generate_trace(num_rows: usize, mismatched: bool) -> Vec<PolynomialValues<F>> {
+            let pair = |i: usize| {
+                let i = F::from_canonical_usize(i % num_rows);
+                (i, i.double())
+            };
+            let shift = if mismatched { 2 } else { 1 };
+            let trace_rows = (0..num_rows)
+                .map(|i| [pair(i).0, pair(i).1, pair(i + 1).0, pair(i + shift).1])
+                .collect::<Vec<_>>();
+            trace_rows_to_poly_values(trace_rows)
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PairPermutationStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize>
+            = StarkFrame<P, P::Scalar, 4, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        type EvaluationFrameTarget = StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, 4, 0>;
+
+        // The lookup constraints are only enforced with a non-zero quotient degree factor.
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+
+        fn lookups(&self) -> Vec<Lookup<F>> {
+            vec![Lookup::permutation(
+                Column::singles([0, 1]).collect(),
+                Column::singles([2, 3]).collect(),
+            )]
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            _vars: &Self::EvaluationFrame<FE, P, D2>,
+            _yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            _builder: &mut CircuitBuilder<F, D>,
+            _vars: &Self::EvaluationFrameTarget,
+            _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+        }
+    }
+
     #[test]
     fn test_pemutations_stark() -> Result<()> {
         const D: usize = 2;
@@ -148,6 +222,46 @@ mod tests {
         verify_stark_proof(stark, proof, &config, None)
     }
 
+    #[test]
+    fn test_pair_permutation_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PairPermutationStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S {
+            _phantom: PhantomData,
+        };
+        let trace = S::generate_trace(1 << 5, false);
+        let proof =
+            prove::<F, C, S, D>(stark, &config, trace, &[], None, &mut TimingTree::default())?;
+        verify_stark_proof(stark, proof.clone(), &config, None)?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
+    #[test]
+    fn test_pair_permutation_stark_mismatched_rows() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PairPermutationStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S {
+            _phantom: PhantomData,
+        };
+        let trace = S::generate_trace(1 << 5, true);
+        // With `debug_assertions`, the prover itself panics on the unsatisfied lookup.
+        let result = std::panic::catch_unwind(|| {
+            let proof =
+                prove::<F, C, S, D>(stark, &config, trace, &[], None, &mut TimingTree::default())?;
+            verify_stark_proof(stark, proof, &config, None)
+        });
+        assert!(!matches!(result, Ok(Ok(()))));
+    }
+
     #[test]
     fn test_permutation_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -157,6 +271,7 @@ mod tests {
 
         let num_rows = 1 << 5;
         let stark = S::new(num_rows);
+        test_stark_constraint_degrees(stark)?;
         test_stark_low_degree(stark)
     }
 
@@ -172,6 +287,26 @@ mod tests {
         test_stark_circuit_constraints::<F, C, S, D>(stark)
     }
 
+    #[test]
+    fn test_permutation_stark_air_export() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PermutationStark<F, D>;
+
+        let stark = S::new(1 << 5);
+        let air = export_air(&stark);
+        assert!(air.constraints.is_empty());
+        assert_eq!(air.lookups.len(), 1);
+
+        let lookup = &air.lookups[0];
+        assert_eq!(lookup.columns[0][0].linear_combination, [(0, 1)]);
+        assert_eq!(lookup.table_column[0].linear_combination, [(1, 1)]);
+        assert!(lookup.frequencies_column.linear_combination.is_empty());
+        assert_eq!(lookup.frequencies_column.constant, 1);
+        assert_eq!(lookup.filter_columns[0].constants[0].constant, 1);
+    }
+
     #[test]
     fn test_recursive_stark_verifier() -> Result<()> {
         init_logger();
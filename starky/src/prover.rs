@@ -10,6 +10,8 @@ use plonky2::field::extension::Extendable;
 use plonky2::field::packable::Packable;
 use plonky2::field::packed::PackedField;
 use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+#[cfg(feature = "std")]
+use plonky2::field::root_cache::cached_lagrange_selectors_on_coset;
 use plonky2::field::types::Field;
 use plonky2::field::zero_poly_coset::ZeroPolyOnCoset;
 use plonky2::fri::oracle::PolynomialBatch;
@@ -20,8 +22,9 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::challenger::Challenger;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::timed;
+use plonky2::util::column_storage::ColumnStorage;
 use plonky2::util::timing::TimingTree;
-use plonky2::util::{log2_ceil, log2_strict, transpose};
+use plonky2::util::{log2_ceil, log2_strict, par_shifted_powers, transpose};
 use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
@@ -29,13 +32,16 @@ use crate::constraint_consumer::ConstraintConsumer;
 use crate::cross_table_lookup::{get_ctl_auxiliary_polys, CtlCheckVars, CtlData};
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::lookup::{
-    get_grand_product_challenge_set, lookup_helper_columns, GrandProductChallengeSet, Lookup,
-    LookupCheckVars,
+    get_grand_product_challenge_set, lookup_helper_columns, GrandProductChallenge,
+    GrandProductChallengeSet, Lookup, LookupCheckVars,
 };
 use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 
+/// The number of rows of the trace LDE transposed at a time when it is kept out of memory.
+const STORAGE_CHUNK_SIZE: usize = 1 << 12;
+
 /// From a STARK trace, computes a STARK proof to attest its correctness.
 pub fn prove<F, C, S, const D: usize>(
     stark: S,
@@ -45,6 +51,33 @@ pub fn prove<F, C, S, const D: usize>(
     verifier_circuit_fri_params: Option<FriParams>,
     timing: &mut TimingTree,
 ) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    prove_with_storage(
+        stark,
+        config,
+        trace_poly_values,
+        public_inputs,
+        verifier_circuit_fri_params,
+        &ColumnStorage::Memory,
+        timing,
+    )
+}
+
+/// Like [`prove`], but keeps the LDE and Merkle leaves of the trace commitment in `storage`.
+/// The auxiliary and quotient commitments are always kept in memory.
+pub fn prove_with_storage<F, C, S, const D: usize>(
+    stark: S,
+    config: &StarkConfig,
+    trace_poly_values: Vec<PolynomialValues<F>>,
+    public_inputs: &[F],
+    verifier_circuit_fri_params: Option<FriParams>,
+    storage: &ColumnStorage,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -83,14 +116,16 @@ where
     let trace_commitment = timed!(
         timing,
         "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
+        PolynomialBatch::<F, C, D>::from_values_with_storage(
             trace_poly_values.clone(),
             rate_bits,
             false,
             cap_height,
             timing,
             None,
-        )
+            storage,
+            STORAGE_CHUNK_SIZE,
+        )?
     );
 
     let trace_cap = trace_commitment.merkle_tree.cap.clone();
@@ -147,22 +182,17 @@ where
         "FRI total reduction arity is too large.",
     );
 
+    config.check_constraint_degree(stark)?;
+    #[cfg(all(debug_assertions, feature = "std"))]
+    debug_check_constraint_degrees(stark);
     let constraint_degree = stark.constraint_degree();
-    assert!(
-        constraint_degree <= (1 << rate_bits) + 1,
-        "The degree of the Stark constraints must be <= blowup_factor + 1"
-    );
 
     // Permutation arguments.
     let lookup_challenges = stark.uses_lookups().then(|| {
         if let Some(c) = ctl_challenges {
-            c.challenges.iter().map(|ch| ch.beta).collect::<Vec<_>>()
+            c.challenges.clone()
         } else {
-            get_grand_product_challenge_set(challenger, config.num_challenges)
-                .challenges
-                .iter()
-                .map(|ch| ch.beta)
-                .collect::<Vec<_>>()
+            get_grand_product_challenge_set(challenger, config.num_challenges).challenges
         }
     });
 
@@ -372,7 +402,7 @@ fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
     stark: &S,
     trace_commitment: &'a PolynomialBatch<F, C, D>,
     auxiliary_polys_commitment: &'a Option<PolynomialBatch<F, C, D>>,
-    lookup_challenges: Option<&'a Vec<F>>,
+    lookup_challenges: Option<&'a Vec<GrandProductChallenge<F>>>,
     lookups: &[Lookup<F>],
     ctl_data: Option<&CtlData<F>>,
     public_inputs: &[F],
@@ -405,11 +435,15 @@ where
     // When opening the `Z`s polys at the "next" point, need to look at the point `next_step` steps away.
     let next_step = 1 << quotient_degree_bits;
 
-    // Evaluation of the first Lagrange polynomial on the LDE domain.
-    let lagrange_first = PolynomialValues::selector(degree, 0).lde_onto_coset(quotient_degree_bits);
-    // Evaluation of the last Lagrange polynomial on the LDE domain.
-    let lagrange_last =
-        PolynomialValues::selector(degree, degree - 1).lde_onto_coset(quotient_degree_bits);
+    // Evaluations of the first and last Lagrange polynomials on the LDE domain, which are the same
+    // for all proofs of this size.
+    #[cfg(feature = "std")]
+    let lagrange_selectors =
+        cached_lagrange_selectors_on_coset::<F>(degree_bits, quotient_degree_bits);
+    #[cfg(not(feature = "std"))]
+    let lagrange_selectors = &[0, degree - 1]
+        .map(|i| PolynomialValues::selector(degree, i).lde_onto_coset(quotient_degree_bits));
+    let [lagrange_first, lagrange_last] = &*lagrange_selectors;
 
     let z_h_on_coset = ZeroPolyOnCoset::<F>::new(degree_bits, quotient_degree_bits);
 
@@ -420,7 +454,7 @@ where
     // Last element of the subgroup.
     let last = F::primitive_root_of_unity(degree_bits).inverse();
     let size = degree << quotient_degree_bits;
-    let coset = F::cyclic_subgroup_coset_known_order(
+    let coset = par_shifted_powers(
         F::primitive_root_of_unity(degree_bits + quotient_degree_bits),
         F::coset_shift(),
         size,
@@ -453,19 +487,23 @@ where
                 &get_trace_values_packed(i_next_start),
                 public_inputs,
             );
+            // Get the local and next row evaluations of all auxiliary polynomials at once,
+            // as they are shared by the permutation argument and the CTLs.
+            let (aux_local_values, aux_next_values) = auxiliary_polys_commitment
+                .as_ref()
+                .map(|commitment| {
+                    (
+                        commitment.get_lde_values_packed::<P>(i_start, step),
+                        commitment.get_lde_values_packed::<P>(i_next_start, step),
+                    )
+                })
+                .unzip();
+
             // Get the local and next row evaluations for the permutation argument,
             // as well as the associated challenges.
             let lookup_vars = lookup_challenges.map(|challenges| LookupCheckVars {
-                local_values: auxiliary_polys_commitment
-                    .as_ref()
-                    .unwrap()
-                    .get_lde_values_packed(i_start, step)[..num_lookup_columns]
-                    .to_vec(),
-                next_values: auxiliary_polys_commitment
-                    .as_ref()
-                    .unwrap()
-                    .get_lde_values_packed(i_next_start, step)[..num_lookup_columns]
-                    .to_vec(),
+                local_values: aux_local_values.as_ref().unwrap()[..num_lookup_columns].to_vec(),
+                next_values: aux_next_values.as_ref().unwrap()[..num_lookup_columns].to_vec(),
                 challenges: challenges.to_vec(),
             });
 
@@ -477,32 +515,23 @@ where
             //     - the `Column`s that form the looking/looked table.
 
             let ctl_vars = ctl_data.map(|data| {
+                let aux_local_values = aux_local_values.as_ref().unwrap();
+                let aux_next_values = aux_next_values.as_ref().unwrap();
                 let mut start_index = 0;
                 data.zs_columns
                     .iter()
                     .enumerate()
                     .map(|(i, zs_columns)| {
                         let num_ctl_helper_cols = num_ctl_columns[i];
-                        let helper_columns = auxiliary_polys_commitment
-                            .as_ref()
-                            .unwrap()
-                            .get_lde_values_packed(i_start, step)
-                            [num_lookup_columns + start_index
-                                ..num_lookup_columns + start_index + num_ctl_helper_cols]
+                        let helper_columns = aux_local_values[num_lookup_columns + start_index
+                            ..num_lookup_columns + start_index + num_ctl_helper_cols]
                             .to_vec();
 
                         let ctl_vars = CtlCheckVars::<F, F, P, 1> {
                             helper_columns,
-                            local_z: auxiliary_polys_commitment
-                                .as_ref()
-                                .unwrap()
-                                .get_lde_values_packed(i_start, step)
-                                [num_lookup_columns + total_num_helper_cols + i],
-                            next_z: auxiliary_polys_commitment
-                                .as_ref()
-                                .unwrap()
-                                .get_lde_values_packed(i_next_start, step)
+                            local_z: aux_local_values
                                 [num_lookup_columns + total_num_helper_cols + i],
+                            next_z: aux_next_values[num_lookup_columns + total_num_helper_cols + i],
                             challenges: zs_columns.challenge,
                             columns: zs_columns.columns.clone(),
                             filter: zs_columns.filter.clone(),
@@ -567,7 +596,7 @@ fn check_constraints<'a, F, C, S, const D: usize>(
     trace_commitment: &'a PolynomialBatch<F, C, D>,
     public_inputs: &[F],
     auxiliary_commitment: &'a Option<PolynomialBatch<F, C, D>>,
-    lookup_challenges: Option<&'a Vec<F>>,
+    lookup_challenges: Option<&'a Vec<GrandProductChallenge<F>>>,
     lookups: &[Lookup<F>],
     ctl_data: Option<&CtlData<F>>,
     alphas: Vec<F>,
@@ -699,3 +728,36 @@ fn check_constraints<'a, F, C, S, const D: usize>(
         }
     }
 }
+
+/// Checks, once per STARK type, that no constraint of the STARK has a degree higher than
+/// [`Stark::constraint_degree`]. Such a constraint would yield an incorrect quotient, which would
+/// only be detected when verifying the proof.
+///
+/// The degrees are profiled from [`Stark::eval_ext_circuit`]. STARKs for which it panics, e.g.
+/// because it is left unimplemented, are not checked. The types are memoized by name since
+/// [`Stark`] does not require `'static`, which [`core::any::TypeId`] would.
+#[cfg(all(debug_assertions, feature = "std"))]
+fn debug_check_constraint_degrees<F, S, const D: usize>(stark: &S)
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    use std::collections::BTreeSet;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::Mutex;
+
+    use crate::stark_testing::check_constraint_degrees;
+
+    static CHECKED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let newly_checked = CHECKED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(core::any::type_name::<S>());
+    if !newly_checked {
+        return;
+    }
+    if let Ok(Err(e)) = catch_unwind(AssertUnwindSafe(|| check_constraint_degrees(stark))) {
+        panic!("{e}");
+    }
+}
@@ -4,8 +4,10 @@
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::iter::once;
+use core::marker::PhantomData;
 
 use anyhow::{ensure, Result};
+use hashbrown::HashMap;
 use itertools::Itertools;
 use plonky2::field::extension::Extendable;
 use plonky2::fri::witness_util::set_fri_proof_target;
@@ -13,9 +15,11 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
-use plonky2::iop::witness::WitnessWrite;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
 use plonky2::util::reducing::ReducingFactorTarget;
 use plonky2::with_context;
 
@@ -89,6 +93,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
 ) where
     C::Hasher: AlgebraicHasher<F>,
 {
+    inner_config.check_constraint_degree(stark).unwrap();
     check_lookup_options(stark, proof, &challenges).unwrap();
 
     let zero = builder.zero();
@@ -157,9 +162,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
             .as_ref()
             .unwrap()
             .challenges
-            .iter()
-            .map(|ch| ch.beta)
-            .collect::<Vec<_>>()
+            .clone()
     });
 
     let lookup_vars = stark.uses_lookups().then(|| LookupCheckVarsTarget {
@@ -311,7 +314,7 @@ pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, con
         .then(|| builder.add_virtual_cap(cap_height));
 
     let quotient_polys_cap =
-        (stark.constraint_degree() > 0).then(|| builder.add_virtual_cap(cap_height));
+        (stark.quotient_degree_factor() > 0).then(|| builder.add_virtual_cap(cap_height));
 
     StarkProofTarget {
         trace_cap: builder.add_virtual_cap(cap_height),
@@ -352,7 +355,7 @@ fn add_virtual_stark_opening_set<F: RichField + Extendable<D>, S: Stark<F, D>, c
         ctl_zs_first: stark
             .requires_ctls()
             .then(|| builder.add_virtual_targets(num_ctl_zs)),
-        quotient_polys: (stark.constraint_degree() > 0).then(|| {
+        quotient_polys: (stark.quotient_degree_factor() > 0).then(|| {
             builder.add_virtual_extension_targets(
                 stark.quotient_degree_factor() * config.num_challenges,
             )
@@ -431,6 +434,140 @@ where
     set_fri_proof_target(witness, &proof_target.opening_proof, &proof.opening_proof)
 }
 
+/// Wraps STARK proofs of a given STARK into plonky2 proofs.
+///
+/// The recursive verifier circuit only depends on the STARK, its configuration and the degree of
+/// the proven trace, so it is built once per trace degree and cached for subsequent proofs. The
+/// public inputs of the STARK proof are exposed as the public inputs of the wrapping proof.
+#[derive(Debug)]
+pub struct StarkProofWrapper<F, C, S, InnerC, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    InnerC: GenericConfig<D, F = F>,
+{
+    stark: S,
+    inner_config: StarkConfig,
+    circuit_config: CircuitConfig,
+    circuits: HashMap<usize, WrapperCircuit<F, C, D>>,
+    _phantom: PhantomData<InnerC>,
+}
+
+/// A circuit of a [`StarkProofWrapper`], along with the targets to fill in.
+#[derive(Debug)]
+struct WrapperCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    data: CircuitData<F, C, D>,
+    proof_target: StarkProofWithPublicInputsTarget<D>,
+    zero: Target,
+}
+
+impl<F, C, S, InnerC, const D: usize> StarkProofWrapper<F, C, S, InnerC, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Copy,
+    InnerC: GenericConfig<D, F = F>,
+    InnerC::Hasher: AlgebraicHasher<F>,
+{
+    /// Creates a wrapper for proofs of `stark` generated with `inner_config`, which will be
+    /// verified in circuits built with `circuit_config`.
+    pub fn new(stark: S, inner_config: &StarkConfig, circuit_config: CircuitConfig) -> Self {
+        Self {
+            stark,
+            inner_config: inner_config.clone(),
+            circuit_config,
+            circuits: HashMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the circuit verifying STARK proofs of traces of length `2^degree_bits`,
+    /// building it if needed.
+    pub fn circuit_data(&mut self, degree_bits: usize) -> &CircuitData<F, C, D> {
+        &self.circuit(degree_bits).data
+    }
+
+    fn circuit(&mut self, degree_bits: usize) -> &WrapperCircuit<F, C, D> {
+        let Self {
+            stark,
+            inner_config,
+            circuit_config,
+            circuits,
+            ..
+        } = self;
+        circuits.entry(degree_bits).or_insert_with(|| {
+            let mut builder = CircuitBuilder::<F, D>::new(circuit_config.clone());
+            let proof_target = add_virtual_stark_proof_with_pis(
+                &mut builder,
+                stark,
+                inner_config,
+                degree_bits,
+                0,
+                0,
+            );
+            builder.register_public_inputs(&proof_target.public_inputs);
+            verify_stark_proof_circuit::<F, InnerC, S, D>(
+                &mut builder,
+                *stark,
+                proof_target.clone(),
+                inner_config,
+                None,
+            );
+            let zero = builder.zero();
+            WrapperCircuit {
+                data: builder.build::<C>(),
+                proof_target,
+                zero,
+            }
+        })
+    }
+
+    /// Proves the verification of the given STARK proof in a plonky2 circuit.
+    pub fn wrap(
+        &mut self,
+        proof: &StarkProofWithPublicInputs<F, InnerC, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let degree_bits = proof.proof.recover_degree_bits(&self.inner_config);
+        let circuit = self.circuit(degree_bits);
+
+        let mut pw = PartialWitness::new();
+        set_stark_proof_with_pis_target(
+            &mut pw,
+            &circuit.proof_target,
+            proof,
+            degree_bits,
+            circuit.zero,
+        )?;
+        circuit.data.prove(pw)
+    }
+}
+
+/// Proves the verification of the given STARK proof in a plonky2 circuit built with the standard
+/// recursion configuration.
+///
+/// The wrapping circuit is built from scratch at each call; use a [`StarkProofWrapper`] to wrap
+/// several proofs of the same STARK, or to retrieve the data needed to verify the wrapping proof.
+pub fn wrap_stark_proof_in_plonky2<F, C, S, InnerC, const D: usize>(
+    stark: S,
+    proof: &StarkProofWithPublicInputs<F, InnerC, D>,
+    config: &StarkConfig,
+) -> Result<ProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Copy,
+    InnerC: GenericConfig<D, F = F>,
+    InnerC::Hasher: AlgebraicHasher<F>,
+{
+    StarkProofWrapper::<F, C, S, InnerC, D>::new(
+        stark,
+        config,
+        CircuitConfig::standard_recursion_config(),
+    )
+    .wrap(proof)
+}
+
 /// Utility function to check that all lookups data wrapped in `Option`s are `Some` iff
 /// the STARK uses a permutation argument.
 fn check_lookup_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
@@ -2,8 +2,9 @@
 
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
+use core::any::type_name;
 
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use plonky2::field::types::{Field, Sample};
@@ -15,6 +16,7 @@ use plonky2::plonk::config::GenericConfig;
 use plonky2::util::{log2_ceil, log2_strict, transpose};
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::constraint_profile::profile_stark_constraints;
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::stark::Stark;
 
@@ -72,6 +74,47 @@ pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const
     Ok(())
 }
 
+/// Tests that no constraint of the given STARK has a degree higher than
+/// [`Stark::constraint_degree`]. Such a constraint would yield an incorrect quotient, which would
+/// only be detected when verifying a proof.
+///
+/// **Note**: the degrees are obtained from [`Stark::eval_ext_circuit`], hence this relies on it
+/// being consistent with [`Stark::eval_packed_generic`].
+pub fn test_stark_constraint_degrees<
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: S,
+) -> Result<()> {
+    check_constraint_degrees(&stark)
+}
+
+/// Checks that no constraint of the given STARK has a degree higher than
+/// [`Stark::constraint_degree`]. Shared by [`test_stark_constraint_degrees`] and the debug check of
+/// the prover.
+pub(crate) fn check_constraint_degrees<F, S, const D: usize>(stark: &S) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let profile = profile_stark_constraints(stark);
+    let exceeding = profile.constraints_exceeding_degree();
+    ensure!(
+        exceeding.is_empty(),
+        "Constraints {:?} of {} have degrees {:?}, exceeding the declared constraint degree {}",
+        exceeding,
+        type_name::<S>(),
+        exceeding
+            .iter()
+            .map(|&i| profile.constraints[i].degree)
+            .collect::<Vec<_>>(),
+        profile.constraint_degree,
+    );
+
+    Ok(())
+}
+
 /// Tests that the circuit constraints imposed by the given STARK are coherent with the native constraints.
 pub fn test_stark_circuit_constraints<
     F: RichField + Extendable<D>,
@@ -141,6 +184,135 @@ pub fn test_stark_circuit_constraints<
     data.verify(proof)
 }
 
+/// A modification of a single trace, used to check that the constraints of a STARK
+/// reject traces that differ from a valid one. See [`test_stark_mutations`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TraceMutation<F: Field> {
+    /// Adds `delta` to the value of `column` at `row`.
+    AddToCell {
+        /// The row of the mutated cell.
+        row: usize,
+        /// The column of the mutated cell.
+        column: usize,
+        /// The value to add to the mutated cell.
+        delta: F,
+    },
+    /// Swaps the rows `row_a` and `row_b`.
+    SwapRows {
+        /// The first row to swap.
+        row_a: usize,
+        /// The second row to swap.
+        row_b: usize,
+    },
+}
+
+impl<F: Field> TraceMutation<F> {
+    /// Applies this mutation to the provided column-wise trace.
+    pub fn apply(&self, trace: &mut [PolynomialValues<F>]) {
+        match *self {
+            Self::AddToCell { row, column, delta } => trace[column].values[row] += delta,
+            Self::SwapRows { row_a, row_b } => {
+                for column in trace.iter_mut() {
+                    column.values.swap(row_a, row_b);
+                }
+            }
+        }
+    }
+
+    /// Outputs one [`TraceMutation::AddToCell`] with a random non-zero delta for each cell of
+    /// the provided columns, over a trace of `num_rows` rows.
+    pub fn all_cells(num_rows: usize, columns: &[usize]) -> Vec<Self> {
+        columns
+            .iter()
+            .flat_map(|&column| {
+                (0..num_rows).map(move |row| {
+                    let delta = F::rand();
+                    Self::AddToCell {
+                        row,
+                        column,
+                        delta: if delta.is_zero() { F::ONE } else { delta },
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Outputs one [`TraceMutation::SwapRows`] for each pair of consecutive rows of a trace
+    /// of `num_rows` rows.
+    pub fn adjacent_row_swaps(num_rows: usize) -> Vec<Self> {
+        (1..num_rows)
+            .map(|row| Self::SwapRows {
+                row_a: row - 1,
+                row_b: row,
+            })
+            .collect()
+    }
+}
+
+/// Tests that the constraints imposed by the given STARK are satisfied by the provided trace,
+/// and that each of the provided mutations of this trace violates at least one of them.
+///
+/// This catches under-constrained tables: a mutation that still satisfies all constraints is
+/// reported as an error. Mutations that leave the trace unchanged (e.g. swapping two identical
+/// rows) are skipped.
+///
+/// **Note**: only the constraints of [`Stark::eval_packed_generic`] are evaluated, not the
+/// lookups or cross-table lookups the STARK may be involved in.
+pub fn test_stark_mutations<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    trace: &[PolynomialValues<F>],
+    public_inputs: &[F],
+    mutations: &[TraceMutation<F>],
+) -> Result<()> {
+    ensure!(
+        first_failing_row(stark, trace, public_inputs).is_none(),
+        "The provided trace does not satisfy the constraints"
+    );
+
+    for mutation in mutations {
+        let mut mutated_trace = trace.to_vec();
+        mutation.apply(&mut mutated_trace);
+        if mutated_trace == trace {
+            continue;
+        }
+        if first_failing_row(stark, &mutated_trace, public_inputs).is_none() {
+            bail!(
+                "The constraints are satisfied by the mutated trace {:?}",
+                mutation
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluates the constraints of the given STARK over all rows of the provided trace,
+/// and outputs the first row at which they do not hold, if any.
+fn first_failing_row<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    trace: &[PolynomialValues<F>],
+    public_inputs: &[F],
+) -> Option<usize> {
+    let num_rows = trace[0].len();
+    let rows = transpose(&trace.iter().map(|c| c.values.clone()).collect::<Vec<_>>());
+    let subgroup = F::two_adic_subgroup(log2_strict(num_rows));
+    let last = subgroup[num_rows - 1];
+    let alpha = F::rand();
+
+    (0..num_rows).find(|&i| {
+        let vars =
+            S::EvaluationFrame::from_values(&rows[i], &rows[(i + 1) % num_rows], public_inputs);
+        let mut consumer = ConstraintConsumer::<F>::new(
+            vec![alpha],
+            subgroup[i] - last,
+            F::from_bool(i == 0),
+            F::from_bool(i == num_rows - 1),
+        );
+        stark.eval_packed_base(&vars, &mut consumer);
+        !consumer.accumulators()[0].is_zero()
+    })
+}
+
 fn random_low_degree_matrix<F: Field>(num_polys: usize, rate_bits: usize) -> Vec<Vec<F>> {
     let polys = (0..num_polys)
         .map(|_| random_low_degree_values(rate_bits))
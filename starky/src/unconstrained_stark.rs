@@ -91,6 +91,7 @@ mod tests {
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
+    use crate::constraint_profile::profile_stark_constraints;
     use crate::proof::StarkProofWithPublicInputs;
     use crate::prover::prove;
     use crate::recursive_verifier::{
@@ -98,7 +99,10 @@ mod tests {
         verify_stark_proof_circuit,
     };
     use crate::stark::Stark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::stark_testing::{
+        test_stark_circuit_constraints, test_stark_constraint_degrees, test_stark_low_degree,
+        test_stark_mutations, TraceMutation,
+    };
     use crate::unconstrained_stark::UnconstrainedStark;
     use crate::verifier::verify_stark_proof;
 
@@ -129,6 +133,7 @@ mod tests {
 
         let num_rows = 1 << 5;
         let stark = S::new(num_rows);
+        test_stark_constraint_degrees(stark)?;
         test_stark_low_degree(stark)
     }
 
@@ -144,6 +149,35 @@ mod tests {
         test_stark_circuit_constraints::<F, C, S, D>(stark)
     }
 
+    #[test]
+    fn test_unconstrained_stark_mutations() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = UnconstrainedStark<F, D>;
+
+        let num_rows = 1 << 5;
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace();
+
+        // Any mutation of an unconstrained trace should go unnoticed.
+        let mutations = TraceMutation::all_cells(num_rows, &[0]);
+        assert!(test_stark_mutations(&stark, &trace, &[], &mutations).is_err());
+    }
+
+    #[test]
+    fn test_unconstrained_stark_constraint_profile() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = UnconstrainedStark<F, D>;
+
+        let stark = S::new(1 << 5);
+        let profile = profile_stark_constraints(&stark);
+        assert!(profile.constraints.is_empty());
+        assert_eq!(profile.unconstrained_columns, [0, 1]);
+    }
+
     #[test]
     fn test_recursive_stark_verifier() -> Result<()> {
         init_logger();
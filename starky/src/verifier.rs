@@ -139,9 +139,7 @@ where
                 .as_ref()
                 .unwrap()
                 .challenges
-                .iter()
-                .map(|ch| ch.beta)
-                .collect::<Vec<_>>(),
+                .clone(),
         )
     } else {
         None
@@ -250,6 +248,7 @@ where
     } = openings;
 
     ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
+    config.check_constraint_degree(stark)?;
 
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;